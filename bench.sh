@@ -0,0 +1,11 @@
+#!/bin/bash
+
+# Roda os benchmarks, guarda uma linha de base do Criterion com o nome da revisão
+# atual e acrescenta as médias em benches/history.csv.
+revision=$(git rev-parse --short HEAD)
+
+echo "Executando os benchmarks ($revision)"
+cargo bench --bench planners -- --save-baseline "$revision"
+
+echo "Registrando em benches/history.csv"
+cargo run --example historico "$revision"
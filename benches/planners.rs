@@ -0,0 +1,223 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use robozinho::batch::strategy;
+use robozinho::bidirectional::bidirectional_search;
+use robozinho::jps::jump_point_search;
+use robozinho::movement::Movement;
+use robozinho::search::{a_star, SearchStats};
+use robozinho::sim::Simulation;
+use robozinho::terrain::Terrain;
+use robozinho::weighted::weighted_a_star;
+
+// Proporções de grama, montanha, pântano, deserto e obstáculo.
+const MIXES: [(&str, [u32; 5]); 3] = [
+    ("grass", [1, 0, 0, 0, 0]),
+    ("mixed", [5, 2, 2, 1, 1]),
+    ("maze", [4, 1, 1, 1, 3]),
+];
+const SIZES: [usize; 3] = [32, 64, 128];
+
+fn generate(size: usize, weights: [u32; 5], seed: u64) -> Vec<Vec<Terrain>> {
+    let terrains = [
+        Terrain::Grass,
+        Terrain::Mountain,
+        Terrain::Swamp,
+        Terrain::Desert,
+        Terrain::Obstacle,
+    ];
+    let total: u32 = weights.iter().sum();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut terrain = vec![vec![Terrain::Grass; size]; size];
+    for column in terrain.iter_mut() {
+        for value in column.iter_mut() {
+            let mut draw = rng.gen_range(0..total);
+            for (terrain, weight) in terrains.iter().zip(weights) {
+                if draw < weight {
+                    *value = *terrain;
+                    break;
+                }
+                draw -= weight;
+            }
+        }
+    }
+    // Cantos livres para a busca de canto a canto.
+    terrain[0][0] = Terrain::Grass;
+    terrain[size - 1][size - 1] = Terrain::Grass;
+    terrain
+}
+
+type Search = fn(&[Vec<Terrain>], (i32, i32), (i32, i32)) -> Option<Vec<(i32, i32)>>;
+
+fn lookup(terrain: &[Vec<Terrain>]) -> impl Fn(i32, i32) -> Terrain + '_ {
+    |x: i32, y: i32| terrain[x as usize][y as usize]
+}
+
+fn size(terrain: &[Vec<Terrain>]) -> (i32, i32) {
+    (terrain.len() as i32, terrain[0].len() as i32)
+}
+
+fn estrela(
+    terrain: &[Vec<Terrain>],
+    start: (i32, i32),
+    goal: (i32, i32),
+) -> Option<Vec<(i32, i32)>> {
+    let (width, height) = size(terrain);
+    let mut stats = SearchStats::default();
+    a_star(
+        start,
+        goal,
+        width,
+        height,
+        lookup(terrain),
+        &Movement::default(),
+        1,
+        1,
+        &mut stats,
+    )
+}
+
+fn guloso(
+    terrain: &[Vec<Terrain>],
+    start: (i32, i32),
+    goal: (i32, i32),
+) -> Option<Vec<(i32, i32)>> {
+    let (width, height) = size(terrain);
+    let mut stats = SearchStats::default();
+    a_star(
+        start,
+        goal,
+        width,
+        height,
+        lookup(terrain),
+        &Movement::default(),
+        0,
+        1,
+        &mut stats,
+    )
+}
+
+fn uniforme(
+    terrain: &[Vec<Terrain>],
+    start: (i32, i32),
+    goal: (i32, i32),
+) -> Option<Vec<(i32, i32)>> {
+    let (width, height) = size(terrain);
+    let mut stats = SearchStats::default();
+    a_star(
+        start,
+        goal,
+        width,
+        height,
+        lookup(terrain),
+        &Movement::default(),
+        1,
+        0,
+        &mut stats,
+    )
+}
+
+fn jps(terrain: &[Vec<Terrain>], start: (i32, i32), goal: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+    let (width, height) = size(terrain);
+    let mut stats = SearchStats::default();
+    jump_point_search(
+        start,
+        goal,
+        width,
+        height,
+        lookup(terrain),
+        &Movement::default(),
+        1,
+        1,
+        &mut stats,
+    )
+}
+
+fn ponderado(
+    terrain: &[Vec<Terrain>],
+    start: (i32, i32),
+    goal: (i32, i32),
+) -> Option<Vec<(i32, i32)>> {
+    let (width, height) = size(terrain);
+    let mut stats = SearchStats::default();
+    weighted_a_star(
+        start,
+        goal,
+        width,
+        height,
+        lookup(terrain),
+        &Movement::default(),
+        1.5,
+        &mut stats,
+    )
+}
+
+fn bidirecional(
+    terrain: &[Vec<Terrain>],
+    start: (i32, i32),
+    goal: (i32, i32),
+) -> Option<Vec<(i32, i32)>> {
+    let (width, height) = size(terrain);
+    let mut stats = SearchStats::default();
+    bidirectional_search(
+        start,
+        goal,
+        width,
+        height,
+        lookup(terrain),
+        &Movement::default(),
+        true,
+        &mut stats,
+    )
+}
+
+const SEARCHES: [(&str, Search); 6] = [
+    ("estrela", estrela),
+    ("guloso", guloso),
+    ("uniforme", uniforme),
+    ("jps", jps),
+    ("ponderado", ponderado),
+    ("bidirecional", bidirecional),
+];
+
+// Uma busca de canto a canto por planejador, tamanho e mistura de terreno.
+fn single_query(c: &mut Criterion) {
+    for (mix, weights) in MIXES {
+        let mut group = c.benchmark_group(format!("query/{}", mix));
+        group.sample_size(10);
+        for size in SIZES {
+            let terrain = generate(size, weights, 42);
+            let goal = (size as i32 - 1, size as i32 - 1);
+            for (name, search) in SEARCHES {
+                group.bench_with_input(BenchmarkId::new(name, size), &terrain, |b, terrain| {
+                    b.iter(|| search(terrain, (0, 0), goal))
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+// Execuções inteiras dos cenários de inputs/, com a mesma semente.
+fn full_run(c: &mut Criterion) {
+    let strategies = ["estrela", "guloso", "uniforme", "jps", "dstar", "hpa"];
+    let mut group = c.benchmark_group("run");
+    group.sample_size(10);
+    for idx in 1..=5 {
+        for name in strategies {
+            let strategy = strategy(name).unwrap();
+            group.bench_with_input(BenchmarkId::new(name, idx), &idx, |b, &idx| {
+                b.iter(|| {
+                    let params = strategy.params(idx);
+                    Simulation::scenario(42, 42, params, 42)
+                        .unwrap()
+                        .run(1_000_000)
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, single_query, full_run);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+use robozinho::board::Board;
+use robozinho::export::*;
+use robozinho::params::Params;
+use robozinho::trajectory::TrajectoryLog;
+use std::env;
+
+// Gera os quadros de uma execução gravada, sem janela nem GPU:
+//   cargo run --example exportar <trajetória> <pasta | arquivo.gif> [a cada N ticks] [ms por quadro]
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut log = match TrajectoryLog::load(&args[1]) {
+        Ok(log) => log,
+        Err(error) => {
+            println!("{}", error);
+            return;
+        }
+    };
+    let output = &args[2];
+    let every = match args.get(3) {
+        Some(every) => every.parse::<usize>().unwrap(),
+        None => 1,
+    };
+    let delay_ms = match args.get(4) {
+        Some(delay) => delay.parse::<u32>().unwrap(),
+        None => 50,
+    };
+
+    let params = Params::for_scenario(log.input_idx);
+    let mut snapshot = Snapshot::new(
+        &Board::new(42, 42),
+        log.input_idx,
+        &params.items_quantity,
+        &params.factories_needs,
+    );
+    let mut renderer = Renderer::new(16, 2);
+    let frames = export(
+        &mut log,
+        &mut snapshot,
+        &mut renderer,
+        output,
+        every,
+        delay_ms,
+    );
+    println!("{} frames written to {}", frames, output);
+}
@@ -0,0 +1,67 @@
+use std::env;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+// Acrescenta a média de cada benchmark da última execução do Criterion em
+// benches/history.csv, uma linha por benchmark com a revisão informada:
+// cargo bench --bench planners && cargo run --example historico <revisão>
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let revision = &args[1];
+    let mut rows: Vec<(String, f64)> = vec![];
+    collect(Path::new("target/criterion"), "", &mut rows);
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let history = Path::new("benches/history.csv");
+    let exists = history.exists();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history)
+        .expect("Something went wrong");
+    if !exists {
+        writeln!(file, "revision,benchmark,mean_ns").unwrap();
+    }
+    for (name, mean) in &rows {
+        writeln!(file, "{},{},{:.0}", revision, name, mean).unwrap();
+    }
+    println!("{} benchmarks written to {}", rows.len(), history.display());
+}
+
+// Cada benchmark tem uma pasta com new/estimates.json; o nome é o caminho até ela.
+fn collect(dir: &Path, prefix: &str, rows: &mut Vec<(String, f64)>) {
+    let estimates = dir.join("new/estimates.json");
+    if estimates.exists() {
+        let contents = fs::read_to_string(estimates).expect("Something went wrong");
+        if let Some(mean) = mean_estimate(&contents) {
+            rows.push((prefix.to_string(), mean));
+        }
+        return;
+    }
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().is_dir() || name == "report" {
+            continue;
+        }
+        let prefix = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        collect(&entry.path(), &prefix, rows);
+    }
+}
+
+// O `point_estimate` dentro de "mean" no JSON do Criterion.
+fn mean_estimate(contents: &str) -> Option<f64> {
+    let mean = &contents[contents.find("\"mean\"")?..];
+    let value = &mean[mean.find("\"point_estimate\":")? + "\"point_estimate\":".len()..];
+    let end = value.find([',', '}'])?;
+    value[..end].trim().parse().ok()
+}
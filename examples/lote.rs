@@ -0,0 +1,52 @@
+use robozinho::batch::*;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+// Roda todas as estratégias nos 5 cenários de inputs/, várias vezes cada, em
+// todos os núcleos, e grava um CSV com uma linha por execução:
+//   cargo run --release --example lote [repetições] [semente] [threads] [saída]
+// Com a mesma semente o CSV sai igual (fora a coluna de tempo) com qualquer
+// número de threads.
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let repetitions = match args.get(1) {
+        Some(repetitions) => repetitions.parse::<usize>().unwrap(),
+        None => 10,
+    };
+    let seed = match args.get(2) {
+        Some(seed) => seed.parse::<u64>().unwrap(),
+        None => 42,
+    };
+    let threads = match args.get(3) {
+        Some(threads) => threads.parse::<usize>().unwrap(),
+        None => 0,
+    };
+    let output = match args.get(4) {
+        Some(output) => output.clone(),
+        None => "outputs/lote.csv".to_string(),
+    };
+
+    let specs = plan(&[1, 2, 3, 4, 5], &STRATEGIES, repetitions, seed);
+    let start = Instant::now();
+    let results = run_batch(&specs, 42, 42, threads, |done, total| {
+        eprint!("\r{}/{} runs", done, total);
+        std::io::stderr().flush().unwrap();
+    });
+    eprintln!();
+
+    if let Some(parent) = Path::new(&output).parent() {
+        fs::create_dir_all(parent).expect("Something went wrong");
+    }
+    fs::write(&output, to_csv(&results)).expect("Something went wrong");
+    let unfinished = results.iter().filter(|r| !r.summary.finished).count();
+    eprintln!(
+        "{} runs in {:.1}s written to {} ({} unfinished)",
+        results.len(),
+        start.elapsed().as_secs_f32(),
+        output,
+        unfinished
+    );
+}
@@ -0,0 +1,27 @@
+use robozinho::report::*;
+use std::env;
+use std::fs;
+
+// Junta os CSV do lote e escreve um relatório com as distribuições de cada
+// estratégia e as comparações pareadas, em Markdown e HTML com gráficos SVG:
+//   cargo run --release --example lote
+//   cargo run --example relatorio <pasta de saída> outputs/lote.csv [outro.csv ...]
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let output = &args[1];
+    let mut records = vec![];
+    for path in &args[2..] {
+        let contents = fs::read_to_string(path).expect("Something went wrong");
+        records.extend(parse_csv(&contents));
+    }
+
+    let report = Report::new(&records);
+    write_report(&report, output);
+    println!(
+        "{} runs of {} strategies summarized in {}/report.md and {}/report.html",
+        report.runs,
+        report.strategies.len(),
+        output,
+        output
+    );
+}
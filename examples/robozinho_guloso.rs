@@ -1,64 +1,86 @@
 use bevy::prelude::*;
 use bevy::window::PresentMode;
 use robozinho::agent::*;
+use robozinho::animation::*;
 use robozinho::board::*;
+use robozinho::clock::*;
+use robozinho::events::*;
 use robozinho::factory::*;
+use robozinho::hud::*;
+use robozinho::overlay::*;
 use robozinho::params::*;
 use robozinho::path::*;
+use robozinho::rng::*;
 use robozinho::tool::*;
+use robozinho::trajectory::*;
+use robozinho::view::*;
+use robozinho::world_event::*;
 use std::env;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let input_idx = &args[1].parse::<usize>().unwrap();
+    let rng = match args.get(2) {
+        Some(seed) => SimRng::new(seed.parse::<u64>().unwrap()),
+        None => SimRng::default(),
+    };
     App::new()
         .insert_resource(WindowDescriptor {
             title: "Robozinho".to_string(),
             width: 700.,
             height: 700.,
-            resizable: false,
+            resizable: true,
             present_mode: PresentMode::Immediate,
             ..default()
         })
         .add_plugins(DefaultPlugins)
+        .add_plugin(SimEventsPlugin)
         .init_resource::<Board>()
         .insert_resource(Path::default())
         .insert_resource(Board::new(42, 42))
-        .insert_resource(Params::new(
-            vec![
-                (ToolType::Battery, 20),
-                (ToolType::WeldingArm, 10),
-                (ToolType::SuctionPump, 8),
-                (ToolType::CoolingDevice, 6),
-                (ToolType::PneumaticArm, 4),
-            ],
-            vec![
-                (ToolType::Battery, 8),
-                (ToolType::WeldingArm, 5),
-                (ToolType::SuctionPump, 2),
-                (ToolType::CoolingDevice, 5),
-                (ToolType::PneumaticArm, 2),
-            ],
-            4,
-            1,
-            0,
-            *input_idx,
-        ))
+        .init_resource::<WorldEvents>()
+        .init_resource::<Overlay>()
+        .init_resource::<SimulationClock>()
+        .init_resource::<Animation>()
+        .insert_resource(rng)
+        .insert_resource(TrajectoryLog::new(format!(
+            "outputs/guloso_{}.trajectory",
+            input_idx
+        )))
+        .insert_resource(Params {
+            g_factor: 0,
+            ..Params::for_scenario(*input_idx)
+        })
         .add_startup_system_to_stage(StartupStage::PreStartup, setup_board)
         .add_startup_system(setup_camera)
+        .add_startup_system(setup_hud)
+        .add_startup_system(setup_metrics_panel)
         .add_startup_system(color_cells)
         .add_startup_system(spawn_tools)
         .add_startup_system(spawn_factories)
         .add_startup_system(setup_agent)
+        .add_startup_system(setup_world_events)
         .add_startup_system_to_stage(StartupStage::PostStartup, setup_agent_factories)
+        .add_system(color_cells)
         .add_system(render_tools)
         .add_system(render_factories)
-        .add_system(move_agent)
-        .add_system(follow_path)
+        .add_system_set(
+            SystemSet::new()
+                .with_run_criteria(clock_tick)
+                .with_system(apply_world_events)
+                .with_system(move_agent)
+                .with_system(follow_path),
+        )
         .add_system(update_agent_factories)
+        .add_system(toggle_overlay)
+        .add_system(control_clock)
+        .add_system(resize_layout)
+        .add_system(control_camera)
+        .add_system(control_animation)
+        .add_system(animate_agent)
+        .add_system(update_hud)
+        .add_system(update_metrics_panel)
+        .add_system(draw_overlay)
+        .add_system_to_stage(CoreStage::Last, save_trajectory)
         .run();
 }
-
-pub fn setup_camera(mut commands: Commands) {
-    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
-}
@@ -17,4 +17,36 @@ do
     cargo run --example robozinho_guloso $i --release > outputs/guloso_$i.txt
     echo "      Dijkstra"
     cargo run --example robozinho_uniforme $i --release > outputs/uniforme_$i.txt
-done
\ No newline at end of file
+    echo "      A* com fronteiras"
+    cargo run --example robozinho_fronteira $i --release > outputs/fronteira_$i.txt
+    echo "      A* com neblina"
+    cargo run --example robozinho_neblina $i --release > outputs/neblina_$i.txt
+    echo "      D* Lite com neblina"
+    cargo run --example robozinho_dstar $i --release > outputs/dstar_$i.txt
+    echo "      A* com eventos"
+    cargo run --example robozinho_eventos $i --release > outputs/eventos_$i.txt
+    echo "      A* com 8 vizinhos"
+    cargo run --example robozinho_diagonal $i --release > outputs/diagonal_$i.txt
+    echo "      Jump Point Search"
+    cargo run --example robozinho_jps $i --release > outputs/jps_$i.txt
+    echo "      HPA*"
+    cargo run --example robozinho_hpa $i --release > outputs/hpa_$i.txt
+    echo "      A* ponderado"
+    cargo run --example robozinho_ponderado $i --release > outputs/ponderado_$i.txt
+    echo "      A* bidirecional"
+    cargo run --example robozinho_bidirecional $i --release > outputs/bidirecional_$i.txt
+    echo "      Dijkstra bidirecional"
+    cargo run --example robozinho_bidirecional_uniforme $i --release > outputs/bidirecional_uniforme_$i.txt
+    echo "      IDA*"
+    cargo run --example robozinho_ida $i --release > outputs/ida_$i.txt
+    echo "      ARA*"
+    cargo run --example robozinho_anytime $i --release > outputs/anytime_$i.txt
+    echo "      A* com cache de distâncias"
+    cargo run --example robozinho_cache $i --release > outputs/cache_$i.txt
+    echo "      A* com planejamento de rota"
+    cargo run --example robozinho_tour $i --release > outputs/tour_$i.txt
+done
+
+echo "Lote com todas as estratégias e relatório"
+cargo run --example lote --release > /dev/null
+cargo run --example relatorio --release outputs/relatorio outputs/lote.csv
@@ -1,17 +1,24 @@
+use crate::animation::{Animation, Motion};
+use crate::belief::Belief;
 use crate::board::Board;
 use crate::cell::Cell;
+use crate::decision::{self, path_cost, random_step, visit_factory, Planners};
+use crate::exploration::*;
 use crate::factory::*;
+use crate::grid::{GridCoord, GridLayout};
 use crate::params::Params;
 use crate::path::*;
+use crate::planner::Planner;
+use crate::poi::{DestinationPolicy, PoiCache};
+use crate::rng::SimRng;
+use crate::search::SearchStats;
 use crate::terrain::Terrain;
 use crate::tool::*;
+use crate::trajectory::*;
+use crate::world_event::spawn_tool;
+use bevy::app::AppExit;
 use bevy::prelude::*;
-use priority_queue::PriorityQueue;
-use rand::distributions::Distribution;
-use rand::distributions::WeightedIndex;
-use std::collections::HashMap;
 use std::fs;
-use bevy::app::AppExit;
 
 #[derive(Default, Component)]
 pub struct Agent {
@@ -19,14 +26,57 @@ pub struct Agent {
     y: usize,
     radius: usize,
     cost: usize,
-    expansions: usize,
+    stats: SearchStats,
     random_moves: usize,
     random_moves_cost: usize,
+    frontier_moves: usize,
     last_move: (i32, i32),
     ended: bool,
     state: Vec<(ToolType, usize)>,
     requisitions: Vec<Factory>,
     destination_queue: Vec<(usize, usize)>,
+    belief: Belief,
+    planners: Planners,
+    poi_cache: PoiCache,
+    last_search: SearchStats,
+}
+
+impl Agent {
+    pub fn position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    pub fn last_search(&self) -> &SearchStats {
+        &self.last_search
+    }
+
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    pub fn cost(&self) -> usize {
+        self.cost
+    }
+
+    pub fn random_moves(&self) -> usize {
+        self.random_moves
+    }
+
+    pub fn random_moves_cost(&self) -> usize {
+        self.random_moves_cost
+    }
+
+    pub fn inventory(&self) -> &[(ToolType, usize)] {
+        &self.state
+    }
+
+    pub fn requisitions(&self) -> &[Factory] {
+        &self.requisitions
+    }
 }
 
 fn read_agent(idx: usize) -> Vec<(usize, usize)> {
@@ -45,84 +95,85 @@ fn read_agent(idx: usize) -> Vec<(usize, usize)> {
     data
 }
 
+// Primeira posição de inputs/agent_<idx> que `free` aceita (sem obstáculo,
+// ferramenta ou fábrica).
+pub fn place_agent<F>(idx: usize, free: F) -> (usize, usize)
+where
+    F: Fn(usize, usize) -> bool,
+{
+    read_agent(idx)
+        .into_iter()
+        .find(|&(x, y)| free(x, y))
+        .unwrap()
+}
+
 pub fn setup_agent(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     board: Res<Board>,
-    windows: Res<Windows>,
-    mut query: Query<&mut Cell>,
+    layout: Res<GridLayout>,
+    query: Query<&Cell>,
     params: Res<Params>,
 ) {
     asset_server.watch_for_changes().unwrap();
 
-    let window = windows.primary();
-    let border_width = 2.0;
-    let cell_width =
-        (window.width() - border_width * (board.width - 1) as f32) / (board.width as f32);
-    let cell_height =
-        (window.height() - border_width * (board.height - 1) as f32) / (board.height as f32);
-
-    let mut idx: usize = 0;
-    let agent_positions = read_agent(params.input_idx);
-
-    let mut cont = 0;
-    while cont < 1 {
-        let (x, y) = agent_positions[idx];
-        idx += 1;
-        let cell = query.get_mut(board.cells[x][y]).unwrap();
-        match cell.terrain {
-            Terrain::Obstacle => {}
-            _ => match cell.tool {
-                Some(_) => {}
-                None => match cell.factory {
-                    Some(_) => {}
-                    None => {
-                        let xx = x as f32;
-                        let yy = y as f32;
-                        let cx = -window.height() / 2.
-                            + cell_height * xx
-                            + border_width * xx
-                            + cell_height / 2.;
-                        let cy = -window.width() / 2.
-                            + cell_width * yy
-                            + border_width * yy
-                            + cell_width / 2.;
-                        commands
-                            .spawn_bundle(SpriteBundle {
-                                texture: asset_server.load("robot.png"),
-                                transform: Transform::from_xyz(cx, cy, 2.0),
-                                sprite: Sprite {
-                                    custom_size: Some(Vec2::new(cell_width, cell_height)),
-                                    ..default()
-                                },
-                                ..default()
-                            })
-                            .insert(Agent {
-                                x: x,
-                                y: y,
-                                radius: params.agent_radius,
-                                cost: 0,
-                                expansions: 0,
-                                random_moves: 0,
-                                random_moves_cost: 0,
-                                last_move: (0, 0),
-                                ended: false,
-                                state: vec![
-                                    (ToolType::Battery, 0),
-                                    (ToolType::WeldingArm, 0),
-                                    (ToolType::SuctionPump, 0),
-                                    (ToolType::CoolingDevice, 0),
-                                    (ToolType::PneumaticArm, 0),
-                                ],
-                                requisitions: vec![],
-                                destination_queue: vec![],
-                            });
-                        cont += 1;
-                    }
-                },
+    let position = place_agent(params.input_idx, |x, y| {
+        let cell = query.get(board.cells[x][y]).unwrap();
+        cell.terrain.passable() && cell.tool.is_none() && cell.factory.is_none()
+    });
+    spawn_agent(
+        &mut commands,
+        &asset_server,
+        &board,
+        &layout,
+        &params,
+        position,
+        vec![],
+    );
+}
+
+// Cria o sprite e o componente do agente em (x, y). `requisitions` costuma vir
+// vazio e ser preenchido por `setup_agent_factories`.
+pub fn spawn_agent(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    board: &Board,
+    layout: &GridLayout,
+    params: &Params,
+    (x, y): (usize, usize),
+    requisitions: Vec<Factory>,
+) {
+    let center = layout.center(GridCoord::new(x, y));
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: asset_server.load("robot.png"),
+            transform: Transform::from_translation(center.extend(2.0)),
+            sprite: Sprite {
+                custom_size: Some(layout.cell_size()),
+                ..default()
             },
-        }
-    }
+            ..default()
+        })
+        .insert(Agent {
+            x,
+            y,
+            radius: params.agent_radius,
+            cost: 0,
+            stats: SearchStats::default(),
+            random_moves: 0,
+            random_moves_cost: 0,
+            frontier_moves: 0,
+            last_move: (0, 0),
+            ended: false,
+            state: decision::empty_inventory(),
+            requisitions,
+            destination_queue: vec![],
+            belief: Belief::new(board.width, board.height),
+            planners: Planners::default(),
+            poi_cache: PoiCache::new(board.width as i32, board.height as i32, params.movement),
+            last_search: SearchStats::default(),
+        })
+        .insert(Motion::at((x, y)));
 }
 
 pub fn setup_agent_factories(mut query: Query<&mut Agent>, query_factories: Query<&Factory>) {
@@ -132,6 +183,42 @@ pub fn setup_agent_factories(mut query: Query<&mut Agent>, query_factories: Quer
     }
 }
 
+// Calcula de uma vez os campos de distância das ferramentas e fábricas, que não
+// mudam de lugar depois que o tabuleiro é montado.
+pub fn setup_poi_cache(
+    board: Res<Board>,
+    mut query: Query<&mut Agent>,
+    query_cell: Query<&Cell>,
+    params: Res<Params>,
+) {
+    if params.destination == DestinationPolicy::Heuristic {
+        return;
+    }
+    let mut agent = query.get_single_mut().unwrap();
+    let agent_ref = &mut *agent;
+    let belief = &agent_ref.belief;
+    let fog_of_war = params.fog_of_war;
+    agent_ref.poi_cache.refresh(|x: i32, y: i32| {
+        if fog_of_war {
+            belief.terrain(x as usize, y as usize)
+        } else {
+            let cell = query_cell.get(board.cells[x as usize][y as usize]).unwrap();
+            cell.terrain
+        }
+    });
+    let mut pois: Vec<(i32, i32)> = vec![];
+    for x in 0..board.width {
+        for y in 0..board.height {
+            let cell = query_cell.get(board.cells[x][y]).unwrap();
+            if cell.tool.is_some() || cell.factory.is_some() {
+                pois.push((x as i32, y as i32));
+            }
+        }
+    }
+    agent_ref.poi_cache.warm(&pois);
+    println!("Cached distance fields: {}", agent_ref.poi_cache.computed);
+}
+
 pub fn update_agent_factories(query: Query<&Agent>, mut query_factories: Query<&mut Factory>) {
     let agent = query.get_single().unwrap();
     for mut factory in query_factories.iter_mut() {
@@ -151,117 +238,37 @@ pub fn check_radius(
     ay: i32,
     r: i32,
     destinations: &mut Vec<(usize, usize)>,
-    requisitions: &Vec<Factory>,
+    requisitions: &[Factory],
     query_cell: &Query<&mut Cell>,
 ) {
-    let width = board.width as i32;
-    let height = board.height as i32;
-    for x in ax - r..=ax + r {
-        for y in ay - r..=ay + r {
-            if x >= 0 && x < width && y >= 0 && y < height {
-                if destinations.contains(&(x as usize, y as usize)) {
-                    continue;
-                }
-                let cell = query_cell.get(board.cells[x as usize][y as usize]).unwrap();
-                match cell.tool {
-                    Some(tool) => {
-                        if search_requisition(tool, &requisitions) {
-                            destinations.push((x as usize, y as usize));
-                        }
-                    }
-                    None => {}
-                }
-            }
-        }
-    }
+    decision::check_radius(
+        (ax, ay),
+        r,
+        board.width,
+        board.height,
+        requisitions,
+        destinations,
+        |x, y| query_cell.get(board.cells[x][y]).unwrap().tool,
+    );
 }
 
 pub fn check_requisitions(agent: &mut Agent) -> bool {
-    let mut batteries: usize = 0;
-    let mut welding_arms: usize = 0;
-    let mut pumps: usize = 0;
-    let mut cooling_devices: usize = 0;
-    let mut pneumatic_arms: usize = 0;
-
-    for (tool_type, quantity) in &agent.state {
-        match tool_type {
-            ToolType::Battery => {
-                batteries = *quantity;
-            }
-            ToolType::WeldingArm => {
-                welding_arms = *quantity;
-            }
-            ToolType::SuctionPump => {
-                pumps = *quantity;
-            }
-            ToolType::CoolingDevice => {
-                cooling_devices = *quantity;
-            }
-            ToolType::PneumaticArm => {
-                pneumatic_arms = *quantity;
-            }
-        }
-    }
-
-    let mut ret = 0;
-
-    for factory in &agent.requisitions {
-        let (x, y) = (factory.x, factory.y);
-        if agent.destination_queue.contains(&(x as usize, y as usize)) {
-            continue;
-        }
-        match factory.needed_tool {
-            Some(ToolType::Battery) => {
-                if batteries >= factory.quantity {
-                    agent.destination_queue.push((x, y));
-                }
-            }
-            Some(ToolType::WeldingArm) => {
-                if welding_arms >= factory.quantity {
-                    agent.destination_queue.push((x, y));
-                }
-            }
-            Some(ToolType::SuctionPump) => {
-                if pumps >= factory.quantity {
-                    agent.destination_queue.push((x, y));
-                }
-            }
-            Some(ToolType::CoolingDevice) => {
-                if cooling_devices >= factory.quantity {
-                    agent.destination_queue.push((x, y));
-                }
-            }
-            Some(ToolType::PneumaticArm) => {
-                if pneumatic_arms >= factory.quantity {
-                    agent.destination_queue.push((x, y));
-                }
-            }
-            None => {
-                ret += 1;
-            }
-        }
-    }
-    ret == agent.requisitions.len()
-}
-
-fn h((ax, ay): (i32, i32), (bx, by): (i32, i32)) -> i32 {
-    (ax - bx).abs() + (ay - by).abs()
+    decision::check_requisitions(
+        &agent.state,
+        &agent.requisitions,
+        &mut agent.destination_queue,
+    )
 }
 
-fn check_next_destination(agent: &Agent) -> usize {
+fn check_next_destination(agent: &mut Agent, params: &Params) -> usize {
     println!("\nChecking next destination");
     println!("Current destination queue: {:?}", agent.destination_queue);
-    let mut min_distance: usize = 1000000000;
-    let mut idx: usize = 0;
-    let mut min_idx: usize = 0;
-    for (x, y) in &agent.destination_queue {
-        let distance = h((agent.x as i32, agent.y as i32), (*x as i32, *y as i32)) as usize;
-        if distance < min_distance {
-            min_distance = distance;
-            min_idx = idx;
-        }
-        idx += 1;
-    }
+    let min_idx = decision::next_destination(
+        agent.position(),
+        &agent.destination_queue,
+        params,
+        &mut agent.poi_cache,
+    );
     println!(
         "My next destination is: {:?}",
         agent.destination_queue[min_idx]
@@ -269,61 +276,59 @@ fn check_next_destination(agent: &Agent) -> usize {
     min_idx
 }
 
-fn search_requisition(tool_type: ToolType, requisitions: &Vec<Factory>) -> bool {
-    for factory in requisitions {
-        if factory.needed_tool == Some(tool_type) {
+fn path_blocked(agent: &Agent, follow_path: &Path) -> bool {
+    let (mut x, mut y) = (agent.x as i32, agent.y as i32);
+    for (mx, my) in &follow_path.moves {
+        x += mx;
+        y += my;
+        if x < 0 || y < 0 || x >= agent.belief.width as i32 || y >= agent.belief.height as i32 {
+            return true;
+        }
+        if !agent.belief.terrain(x as usize, y as usize).passable() {
             return true;
         }
     }
     false
 }
 
-fn valid(x: i32, y: i32, width: i32, height: i32) -> bool {
-    if x < 0 {
-        return false;
-    }
-    if y < 0 {
-        return false;
-    }
-    if x >= width {
-        return false;
-    }
-    if y >= height {
-        return false;
+fn abort_path(agent: &mut Agent, follow_path: &mut Path) {
+    follow_path.moves.clear();
+    if let Some(destination) = follow_path.destination.take() {
+        if !agent.destination_queue.contains(&destination) {
+            agent.destination_queue.push(destination);
+        }
     }
-    true
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn move_agent(
-    windows: Res<Windows>,
+    animation: Res<Animation>,
     board: Res<Board>,
     mut follow_path: ResMut<Path>,
-    mut query: Query<(&mut Agent, &mut Transform)>,
+    mut query: Query<(&mut Agent, &mut Motion)>,
     mut query_cell: Query<&mut Cell>,
     mut query_tool: Query<&mut Tool>,
     params: Res<Params>,
+    mut rng: ResMut<SimRng>,
+    mut log: ResMut<TrajectoryLog>,
     mut exit: EventWriter<AppExit>,
 ) {
     if follow_path.moves.is_empty() {
         // let time = time::Duration::from_secs_f32(0.1);
         // thread::sleep(time);
 
-        let moves: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-
-        let g_factor = params.g_factor;
-        let h_factor = params.h_factor;
-
-        let window = windows.primary();
-        let border_width = 2.0;
-        let cell_width =
-            (window.width() - border_width * (board.width - 1) as f32) / (board.width as f32);
-        let cell_height =
-            (window.height() - border_width * (board.height - 1) as f32) / (board.height as f32);
-
-        let (mut agent, mut transform) = query.get_single_mut().unwrap();
+        let (mut agent, mut motion) = query.get_single_mut().unwrap();
+        let from = agent.position();
 
         let mut cell = query_cell.get_mut(board.cells[agent.x][agent.y]).unwrap();
 
+        if let Some(tool) = cell.tool {
+            log.push(TrajectoryEvent::Pickup {
+                x: agent.x,
+                y: agent.y,
+                tool,
+            });
+        }
         match cell.tool {
             Some(ToolType::Battery) => {
                 agent.state[0].1 += 1;
@@ -361,10 +366,18 @@ pub fn move_agent(
         if check_requisitions(&mut agent) {
             if !agent.ended {
                 println!("\n\nEnd of execution");
-                println!("Final cost: {}", agent.cost);
-                println!("Number of expansions: {}", agent.expansions);
+                let scale = params.movement.scale() as f64;
+                println!("Final cost: {}", agent.cost as f64 / scale);
+                println!("Number of expansions: {}", agent.stats.expansions);
+                println!("Peak open list size: {}", agent.stats.peak_open);
                 println!("Random moves: {}", agent.random_moves);
-                println!("Random moves cost: {}", agent.random_moves_cost);
+                println!(
+                    "Random moves cost: {}",
+                    agent.random_moves_cost as f64 / scale
+                );
+                if params.exploration == ExplorationPolicy::Frontier {
+                    println!("Frontier moves: {}", agent.frontier_moves);
+                }
                 agent.ended = true;
             }
             exit.send(AppExit);
@@ -385,6 +398,8 @@ pub fn move_agent(
             &requisitions,
             &query_cell,
         );
+        let (ax, ay, r) = (agent.x as i32, agent.y as i32, agent.radius as i32);
+        agent.belief.observe(&board, ax, ay, r, &query_cell);
 
         // Implementar A* aqui
 
@@ -393,39 +408,28 @@ pub fn move_agent(
         let width = board.width as i32;
         let height = board.height as i32;
 
+        if params.destination != DestinationPolicy::Heuristic && !agent.destination_queue.is_empty()
+        {
+            let agent_ref = &mut *agent;
+            let belief = &agent_ref.belief;
+            let fog_of_war = params.fog_of_war;
+            agent_ref.poi_cache.refresh(|x: i32, y: i32| {
+                if fog_of_war {
+                    belief.terrain(x as usize, y as usize)
+                } else {
+                    let cell = query_cell.get(board.cells[x as usize][y as usize]).unwrap();
+                    cell.terrain
+                }
+            });
+        }
+
         if agent.destination_queue.len() > 0 {
-            let next_idx = check_next_destination(&agent);
+            let next_idx = check_next_destination(&mut agent, &params);
             let (dx, dy) = agent.destination_queue.remove(next_idx);
             println!("Finding path to ({}, {})", dx, dy);
             let cell = query_cell.get(board.cells[dx][dy]).unwrap();
-            let mut should_find_path = true;
-            match cell.tool {
-                Some(ToolType::Battery) => {
-                    should_find_path = search_requisition(ToolType::Battery, &agent.requisitions)
-                }
-                Some(ToolType::WeldingArm) => {
-                    should_find_path = search_requisition(ToolType::WeldingArm, &agent.requisitions)
-                }
-                Some(ToolType::SuctionPump) => {
-                    should_find_path =
-                        search_requisition(ToolType::SuctionPump, &agent.requisitions)
-                }
-                Some(ToolType::CoolingDevice) => {
-                    should_find_path =
-                        search_requisition(ToolType::CoolingDevice, &agent.requisitions)
-                }
-                Some(ToolType::PneumaticArm) => {
-                    should_find_path =
-                        search_requisition(ToolType::PneumaticArm, &agent.requisitions)
-                }
-                None => match cell.factory {
-                    Some(factory) => match factory.needed_tool {
-                        Some(_) => {}
-                        None => should_find_path = false,
-                    },
-                    None => should_find_path = false,
-                },
-            }
+            let should_find_path =
+                decision::should_find_path(cell.tool, cell.factory, &agent.requisitions);
 
             println!(
                 "Should I find a path to ({}, {}): {}",
@@ -434,266 +438,163 @@ pub fn move_agent(
 
             if should_find_path {
                 let (ax, ay) = (agent.x as i32, agent.y as i32);
-                let mut pq = PriorityQueue::new();
-                let mut partial_cost: HashMap<(i32, i32), i32> = HashMap::new();
-                let mut final_cost = -1000000;
-                let mut path: HashMap<(i32, i32), Vec<(i32, i32)>> = HashMap::new();
-                pq.push((ax, ay, 0, 0), 0);
-                while !pq.is_empty() {
-                    let ((cx, cy, mvx, mvy), cost) = pq.pop().unwrap();
-                    if -cost >= -final_cost {
-                        continue;
-                    }
-                    if partial_cost.contains_key(&(cx, cy)) && -cost >= -partial_cost[&(cx, cy)] {
-                        continue;
+                let goal = (dx as i32, dy as i32);
+                let mut stats = SearchStats::default();
+                let agent_ref = &mut *agent;
+                let belief = &agent_ref.belief;
+                let fog_of_war = params.fog_of_war;
+                let terrain = |x: i32, y: i32| {
+                    if fog_of_war {
+                        belief.terrain(x as usize, y as usize)
+                    } else {
+                        let cell = query_cell.get(board.cells[x as usize][y as usize]).unwrap();
+                        cell.terrain
                     }
-                    println!("\nGoing from ({}, {}) to ({}, {})", cx, cy, dx, dy);
-                    if path.contains_key(&(cx, cy)) {
-                        println!("Current path: {:?}", path[(&(cx, cy))]);
-                    }
-                    println!("Last move: ({}, {})", mvx, mvy);
-                    println!("Current cost: {}", -cost);
-                    if partial_cost.contains_key(&(cx, cy)) {
-                        println!("Partial cost: {}", -partial_cost[&(cx, cy)]);
-                    }
-                    partial_cost.remove(&(cx, cy));
-                    partial_cost.insert((cx, cy), cost);
-                    // match path.remove(&(cx, cy)) {
-                    //     Some(mut v) => {
-                    //         v.push((mvx, mvy));
-                    //         path.insert((cx, cy), v);
-                    //     }usize
-                    //     None => {
-                    //         let v = vec![(mvx, mvy)];
-                    //         path.insert((cx, cy), v);
-                    //     }
-                    // }
-                    if cx == dx as i32 && cy == dy as i32 {
-                        final_cost = cost;
-                        while !pq.is_empty() {
-                            pq.pop();
-                        }
-                        break;
-                    }
-                    agent.expansions += 1;
-                    for (mx, my) in moves {
-                        let mxx = mx;
-                        let myy = my;
-                        let (nx, ny) = (cx + mxx, cy + myy);
-                        if !valid(nx, ny, width, height) {
-                            continue;
-                        }
-                        let n_cell = query_cell
-                            .get(board.cells[nx as usize][ny as usize])
-                            .unwrap();
-                        let g: i32 = match n_cell.terrain {
-                            Terrain::Grass => 1,
-                            Terrain::Mountain => 5,
-                            Terrain::Swamp => 10,
-                            Terrain::Desert => 20,
-                            Terrain::Obstacle => -1,
-                        };
-                        let n_cost =
-                            cost - g * g_factor - h((nx, ny), (dx as i32, dy as i32)) * h_factor;
-                        if partial_cost.contains_key(&(nx, ny))
-                            && -n_cost >= -partial_cost[&(nx, ny)]
-                        {
-                            continue;
-                        }
-                        if path.contains_key(&(nx, ny)) {
-                            path.remove(&(nx, ny));
-                        }
-                        let mut partial_path: Vec<(i32, i32)> = vec![];
-                        if path.contains_key(&(cx, cy)) {
-                            for (xx, yy) in &path[&(cx, cy)] {
-                                partial_path.push((*xx, *yy));
-                            }
-                        }
-                        partial_path.push((mxx, myy));
-                        path.insert((nx, ny), partial_path);
-                        pq.push((nx, ny, mxx, myy), n_cost);
-                    }
-                }
-                //Agora precisamos pegar o caminho de "menor" custo
-                println!(
-                    "\nDistance between ({}, {}) and ({}, {}): {}",
-                    ax, ay, dx, dy, -final_cost
+                };
+                let path = agent_ref.planners.plan(
+                    (ax, ay),
+                    goal,
+                    width,
+                    height,
+                    terrain,
+                    &params,
+                    &mut stats,
                 );
-                if path.contains_key(&(dx as i32, dy as i32)) {
-                    for (mx, my) in &path[&(dx as i32, dy as i32)] {
-                        follow_path.moves.push((*mx, *my));
-                    }
-                    println!("Path: {:?}", path[&(dx as i32, dy as i32)]);
-                } else {
-                    follow_path.moves.push((0, 0));
+                if params.planner == Planner::Hierarchical {
+                    println!("Clusters rebuilt: {}", agent_ref.planners.rebuilt);
+                }
+                // Sem solução ainda: o agente espera um tick e a busca do ARA*
+                // continua de onde parou.
+                if path.is_none() && agent_ref.planners.searching() {
+                    agent_ref.destination_queue.push((dx, dy));
+                }
+                log.push(TrajectoryEvent::Plan {
+                    from: (ax as usize, ay as usize),
+                    to: (dx, dy),
+                    cost: path
+                        .as_ref()
+                        .map(|moves| path_cost((ax, ay), moves, &params.movement, terrain)),
+                });
+                agent.stats.add(&stats);
+                agent.last_search = stats;
+                follow_path.destination = Some((dx, dy));
+                match path {
+                    Some(moves) => follow_path.moves = moves,
+                    None => follow_path.moves.push((0, 0)),
                 }
 
                 // let time = time::Duration::from_secs_f32(15.0);
                 // thread::sleep(time);
             }
         } else {
-            let mut weights: [f32; 4] = [0.0, 0.0, 0.0, 0.0];
-            let mut has_option = false;
-            let mountain_cost = 5.0;
-            let swamp_cost = 10.0;
-            let desert_cost = 20.0;
-
-            if agent.x < board.height - 1 && agent.last_move != moves[0] {
-                let x = agent.x + 1;
-                let y = agent.y;
-                let cell = query_cell.get(board.cells[x][y]).unwrap();
-                match cell.terrain {
-                    Terrain::Grass => {
-                        weights[0] = 1.0;
-                        has_option = true;
-                    }
-                    Terrain::Mountain => {
-                        weights[0] = 1.0 / mountain_cost;
-                        has_option = true;
-                    }
-                    Terrain::Swamp => {
-                        weights[0] = 1.0 / swamp_cost;
-                        has_option = true;
-                    }
-                    Terrain::Desert => {
-                        weights[0] = 1.0 / desert_cost;
-                        has_option = true;
-                    }
-                    _ => {}
-                }
-            }
-            if agent.y < board.width - 1 && agent.last_move != moves[1] {
-                let x = agent.x;
-                let y = agent.y + 1;
-                let cell = query_cell.get(board.cells[x][y]).unwrap();
-                match cell.terrain {
-                    Terrain::Grass => {
-                        weights[1] = 1.0;
-                        has_option = true;
-                    }
-                    Terrain::Mountain => {
-                        weights[1] = 1.0 / mountain_cost;
-                        has_option = true;
-                    }
-                    Terrain::Swamp => {
-                        weights[1] = 1.0 / swamp_cost;
-                        has_option = true;
-                    }
-                    Terrain::Desert => {
-                        weights[1] = 1.0 / desert_cost;
-                        has_option = true;
-                    }
-                    _ => {}
-                }
-            }
-            if agent.x > 0 && agent.last_move != moves[2] {
-                let x = agent.x - 1;
-                let y = agent.y;
-                let cell = query_cell.get(board.cells[x][y]).unwrap();
-                match cell.terrain {
-                    Terrain::Grass => {
-                        weights[2] = 1.0;
-                        has_option = true;
-                    }
-                    Terrain::Mountain => {
-                        weights[2] = 1.0 / mountain_cost;
-                        has_option = true;
-                    }
-                    Terrain::Swamp => {
-                        weights[2] = 1.0 / swamp_cost;
-                        has_option = true;
-                    }
-                    Terrain::Desert => {
-                        weights[2] = 1.0 / desert_cost;
-                        has_option = true;
-                    }
-                    _ => {}
-                }
-            }
-            if agent.y > 0 && agent.last_move != moves[3] {
-                let x = agent.x;
-                let y = agent.y - 1;
-                let cell = query_cell.get(board.cells[x][y]).unwrap();
-                match cell.terrain {
-                    Terrain::Grass => {
-                        weights[3] = 1.0;
-                        has_option = true;
-                    }
-                    Terrain::Mountain => {
-                        weights[3] = 1.0 / mountain_cost;
-                        has_option = true;
-                    }
-                    Terrain::Swamp => {
-                        weights[3] = 1.0 / swamp_cost;
-                        has_option = true;
-                    }
-                    Terrain::Desert => {
-                        weights[3] = 1.0 / desert_cost;
-                        has_option = true;
-                    }
-                    _ => {}
-                }
-            }
+            let frontier = match params.exploration {
+                ExplorationPolicy::Frontier => frontier_step(
+                    &agent.belief,
+                    agent.x as i32,
+                    agent.y as i32,
+                    agent.radius as i32,
+                    &params.movement,
+                ),
+                ExplorationPolicy::RandomWalk => None,
+            };
 
-            if has_option {
-                let dist = WeightedIndex::new(&weights).unwrap();
-                let mut rng = rand::thread_rng();
-                let movement = moves[dist.sample(&mut rng)];
-                agent.last_move = movement;
-                agent.last_move.0 *= -1;
-                agent.last_move.1 *= -1;
-                let new_x: usize = (agent.x as i32 + movement.0) as usize;
-                let new_y: usize = (agent.y as i32 + movement.1) as usize;
-                agent.x = new_x;
-                agent.y = new_y;
-                println!("\nRandom move: ({} {})", movement.0, movement.1);
-                println!("Agent randomly moved to: ({} {})", agent.x, agent.y);
-                let cell = query_cell.get(board.cells[new_x][new_y]).unwrap();
-                agent.random_moves_cost += match cell.terrain {
-                    Terrain::Grass => 1,
-                    Terrain::Mountain => 5,
-                    Terrain::Swamp => 10,
-                    Terrain::Desert => 20,
-                    Terrain::Obstacle => 1e9 as usize,
+            if let Some(movement) = frontier {
+                agent.last_move = (-movement.0, -movement.1);
+                agent.x = (agent.x as i32 + movement.0) as usize;
+                agent.y = (agent.y as i32 + movement.1) as usize;
+                println!("\nFrontier move: ({} {})", movement.0, movement.1);
+                println!("Agent moved towards frontier: ({} {})", agent.x, agent.y);
+                let cell = query_cell.get(board.cells[agent.x][agent.y]).unwrap();
+                // Passo escolhido de propósito: entra no custo como os do caminho
+                let cost = params.movement.step_cost(movement, cell.terrain);
+                agent.cost += cost;
+                agent.frontier_moves += 1;
+                log.push(TrajectoryEvent::Move {
+                    x: agent.x,
+                    y: agent.y,
+                    cost,
+                    random: false,
+                });
+            } else {
+                let movement_model = &params.movement;
+                let (ax, ay) = (agent.x as i32, agent.y as i32);
+                let terrain = |x: i32, y: i32| {
+                    let cell = query_cell.get(board.cells[x as usize][y as usize]).unwrap();
+                    cell.terrain
                 };
-                agent.random_moves += 1;
+                let step = random_step(
+                    (ax, ay),
+                    agent.last_move,
+                    width,
+                    height,
+                    movement_model,
+                    terrain,
+                    &mut **rng,
+                );
+
+                if let Some(movement) = step {
+                    agent.last_move = (-movement.0, -movement.1);
+                    let new_x: usize = (agent.x as i32 + movement.0) as usize;
+                    let new_y: usize = (agent.y as i32 + movement.1) as usize;
+                    agent.x = new_x;
+                    agent.y = new_y;
+                    println!("\nRandom move: ({} {})", movement.0, movement.1);
+                    println!("Agent randomly moved to: ({} {})", agent.x, agent.y);
+                    let cell = query_cell.get(board.cells[new_x][new_y]).unwrap();
+                    let cost = movement_model.step_cost(movement, cell.terrain);
+                    agent.random_moves_cost += cost;
+                    agent.random_moves += 1;
+                    log.push(TrajectoryEvent::Move {
+                        x: new_x,
+                        y: new_y,
+                        cost,
+                        random: true,
+                    });
+                }
             }
 
-            let x = agent.x as f32;
-            let y = agent.y as f32;
-            let cx = -window.width() / 2. + cell_width * x + border_width * x + cell_width / 2.;
-            let cy = -window.height() / 2. + cell_height * y + border_width * y + cell_height / 2.;
-            let translation = &mut transform.translation;
-            translation.x = cx;
-            translation.y = cy;
+            if agent.position() != from {
+                let cell = query_cell.get(board.cells[agent.x][agent.y]).unwrap();
+                motion.start(from, agent.position(), animation.duration(cell.terrain));
+            }
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn follow_path(
-    windows: Res<Windows>,
+    animation: Res<Animation>,
     board: Res<Board>,
     mut follow_path: ResMut<Path>,
-    mut query: Query<(&mut Agent, &mut Transform)>,
+    mut query: Query<(&mut Agent, &mut Motion)>,
     mut query_cell: Query<&mut Cell>,
     mut query_tool: Query<&mut Tool>,
+    params: Res<Params>,
+    mut log: ResMut<TrajectoryLog>,
 ) {
     if !follow_path.moves.is_empty() {
         // let time = time::Duration::from_secs_f32(0.1);
         // thread::sleep(time);
-        let window = windows.primary();
-        let border_width = 2.0;
-        let cell_width =
-            (window.width() - border_width * (board.width - 1) as f32) / (board.width as f32);
-        let cell_height =
-            (window.height() - border_width * (board.height - 1) as f32) / (board.height as f32);
-
-        let (mut agent, mut transform) = query.get_single_mut().unwrap();
+
+        let (mut agent, mut motion) = query.get_single_mut().unwrap();
         println!("\nCurrent agent position: ({}, {})", agent.x, agent.y);
         println!("Current follow path size: {}", follow_path.moves.len());
-        let (mx, my) = follow_path.moves.remove(0);
+        let (mx, my) = follow_path.moves[0];
+        let (nx, ny) = (agent.x as i32 + mx, agent.y as i32 + my);
+        let next = query_cell
+            .get(board.cells[nx as usize][ny as usize])
+            .unwrap();
+        if (mx, my) != (0, 0) && !next.terrain.passable() {
+            println!("Next step ({} {}) is blocked, replanning", nx, ny);
+            let (ax, ay, r) = (agent.x as i32, agent.y as i32, agent.radius as i32);
+            agent.belief.observe(&board, ax, ay, r, &query_cell);
+            abort_path(&mut agent, &mut follow_path);
+            return;
+        }
+        follow_path.moves.remove(0);
         println!("Current movement: ({} {})", mx, my);
         if !(mx == 0 && my == 0) {
+            let from = agent.position();
             agent.x = (agent.x as i32 + mx) as usize;
             agent.y = (agent.y as i32 + my) as usize;
             println!("New agent position: ({} {})", agent.x, agent.y);
@@ -712,18 +613,17 @@ pub fn follow_path(
                 &requisitions,
                 &query_cell,
             );
-
-            let x = agent.x as f32;
-            let y = agent.y as f32;
-            let cx = -window.width() / 2. + cell_width * x + border_width * x + cell_width / 2.;
-            let cy = -window.height() / 2. + cell_height * y + border_width * y + cell_height / 2.;
-            let translation = &mut transform.translation;
-            translation.x = cx;
-            translation.y = cy;
+            let (ax, ay, r) = (agent.x as i32, agent.y as i32, agent.radius as i32);
+            let discovered = agent.belief.observe(&board, ax, ay, r, &query_cell);
+            if params.fog_of_war && !discovered.is_empty() && path_blocked(&agent, &follow_path) {
+                println!("Obstacle discovered on the current path, replanning");
+                abort_path(&mut agent, &mut follow_path);
+            }
 
             let x = agent.x;
             let y = agent.y;
             let mut cell = query_cell.get_mut(board.cells[x][y]).unwrap();
+            motion.start(from, (x, y), animation.duration(cell.terrain));
 
             print!("Terrain at position ({} {}): ", x, y);
             match cell.terrain {
@@ -734,14 +634,18 @@ pub fn follow_path(
                 Terrain::Obstacle => println!("Obstacle"),
             }
 
-            agent.cost += match cell.terrain {
-                Terrain::Grass => 1,
-                Terrain::Mountain => 5,
-                Terrain::Swamp => 10,
-                Terrain::Desert => 20,
-                Terrain::Obstacle => 1e9 as usize,
-            };
+            let cost = params.movement.step_cost((mx, my), cell.terrain);
+            agent.cost += cost;
+            log.push(TrajectoryEvent::Move {
+                x,
+                y,
+                cost,
+                random: false,
+            });
 
+            if let Some(tool) = cell.tool {
+                log.push(TrajectoryEvent::Pickup { x, y, tool });
+            }
             match cell.tool {
                 Some(ToolType::Battery) => {
                     agent.state[0].1 += 1;
@@ -775,109 +679,190 @@ pub fn follow_path(
                     tool.tool_type = None;
                 }
             }
-            match cell.factory {
-                Some(mut factory) => {
-                    let mut batteries = 0;
-                    let mut welding_arms = 0;
-                    let mut pumps = 0;
-                    let mut cooling_devices = 0;
-                    let mut pneumatic_arms = 0;
-                    for (tool_type, quantity) in &agent.state {
-                        match tool_type {
-                            ToolType::Battery => {
-                                batteries = *quantity;
-                            }
-                            ToolType::WeldingArm => {
-                                welding_arms = *quantity;
-                            }
-                            ToolType::SuctionPump => {
-                                pumps = *quantity;
-                            }
-                            ToolType::CoolingDevice => {
-                                cooling_devices = *quantity;
-                            }
-                            ToolType::PneumaticArm => {
-                                pneumatic_arms = *quantity;
-                            }
-                        }
+            // A fábrica da célula guarda a requisição original: pisar nela encerra
+            // a requisição do agente, com ou sem entrega.
+            if let Some(factory) = cell.factory {
+                let agent_ref = &mut *agent;
+                let delivered = visit_factory(
+                    &factory,
+                    &mut agent_ref.state,
+                    &mut agent_ref.requisitions,
+                    &mut agent_ref.destination_queue,
+                );
+                if let Some((tool, quantity)) = delivered {
+                    println!("Drop {} {:?} at ({} {})", quantity, tool, x, y);
+                    log.push(TrajectoryEvent::Drop {
+                        x,
+                        y,
+                        tool,
+                        quantity,
+                    });
+                    log.push(TrajectoryEvent::Deliver { x, y });
+                }
+            }
+        }
+    }
+}
+
+// Usa a sobra de cada tick para melhorar a solução do ARA*. Quando sai uma
+// solução melhor o caminho é refeito a partir da posição atual do agente.
+pub fn improve_anytime_path(
+    board: Res<Board>,
+    mut follow_path: ResMut<Path>,
+    mut query: Query<&mut Agent>,
+    query_cell: Query<&Cell>,
+    params: Res<Params>,
+) {
+    if params.planner != Planner::Anytime || follow_path.moves.is_empty() {
+        return;
+    }
+    let mut agent = query.get_single_mut().unwrap();
+    let agent_ref = &mut *agent;
+    let mut ara = match agent_ref.planners.ara.take() {
+        Some(ara) if !ara.finished() => ara,
+        ara => {
+            agent_ref.planners.ara = ara;
+            return;
+        }
+    };
+    let (gx, gy) = ara.goal();
+    if follow_path.destination == Some((gx as usize, gy as usize)) {
+        let mut stats = SearchStats::default();
+        let belief = &agent_ref.belief;
+        let fog_of_war = params.fog_of_war;
+        let terrain = |x: i32, y: i32| {
+            if fog_of_war {
+                belief.terrain(x as usize, y as usize)
+            } else {
+                let cell = query_cell.get(board.cells[x as usize][y as usize]).unwrap();
+                cell.terrain
+            }
+        };
+        if ara.improve(terrain, params.anytime_budget, &mut stats) {
+            if let Some(moves) = ara.path_from((agent_ref.x as i32, agent_ref.y as i32)) {
+                follow_path.moves = moves;
+                let destination = (gx as usize, gy as usize);
+                agent_ref.destination_queue.retain(|d| *d != destination);
+            }
+        }
+        agent_ref.stats.add(&stats);
+    }
+    agent_ref.planners.ara = Some(ara);
+}
+
+// Grava a trajetória quando o app fecha: no fim da execução ou quando a janela é
+// fechada, que também manda `AppExit`. Assim uma execução que travou ainda pode
+// ser reproduzida. Roda em `CoreStage::Last` para ver o `AppExit` do mesmo quadro.
+pub fn save_trajectory(
+    mut exit: EventReader<AppExit>,
+    mut log: ResMut<TrajectoryLog>,
+    params: Res<Params>,
+    rng: Res<SimRng>,
+) {
+    if exit.iter().next().is_none() {
+        return;
+    }
+    log.input_idx = params.input_idx;
+    log.seed = rng.seed;
+    log.movement = params.movement;
+    log.save();
+}
+
+// Modo de reprodução: em vez de planejar, aplica a cada tick os eventos do
+// registro carregado, movendo o mesmo sprite do agente e atualizando ferramentas e
+// fábricas como na execução original.
+#[allow(clippy::too_many_arguments)]
+pub fn replay_agent(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    layout: Res<GridLayout>,
+    board: Res<Board>,
+    mut follow_path: ResMut<Path>,
+    mut log: ResMut<TrajectoryLog>,
+    mut query: Query<(&mut Agent, &mut Motion)>,
+    mut query_cell: Query<&mut Cell>,
+    mut query_tool: Query<&mut Tool>,
+    params: Res<Params>,
+    animation: Res<Animation>,
+) {
+    let (mut agent, mut motion) = query.get_single_mut().unwrap();
+    if log.finished() {
+        if !agent.ended {
+            let scale = params.movement.scale() as f64;
+            println!("\n\nEnd of replay");
+            println!("Final cost: {}", agent.cost as f64 / scale);
+            println!("Random moves: {}", agent.random_moves);
+            println!(
+                "Random moves cost: {}",
+                agent.random_moves_cost as f64 / scale
+            );
+            agent.ended = true;
+        }
+        return;
+    }
+
+    for event in log.next_tick() {
+        match event {
+            TrajectoryEvent::Plan { from, to, cost } => {
+                println!("\nPlan from {:?} to {:?}, cost {:?}", from, to, cost);
+                follow_path.destination = Some(to);
+            }
+            TrajectoryEvent::Move { x, y, cost, random } => {
+                let terrain = query_cell.get(board.cells[x][y]).unwrap().terrain;
+                motion.start(agent.position(), (x, y), animation.duration(terrain));
+                agent.x = x;
+                agent.y = y;
+                if random {
+                    agent.random_moves += 1;
+                    agent.random_moves_cost += cost;
+                } else {
+                    agent.cost += cost;
+                }
+            }
+            TrajectoryEvent::Pickup { x, y, tool } => {
+                println!("Got 1 {:?} at {} {}", tool, x, y);
+                let mut cell = query_cell.get_mut(board.cells[x][y]).unwrap();
+                cell.tool = None;
+                for mut sprite in query_tool.iter_mut() {
+                    if sprite.x == x && sprite.y == y {
+                        sprite.tool_type = None;
                     }
-                    let fx = factory.x;
-                    let fy = factory.y;
-                    match factory.needed_tool {
-                        Some(ToolType::Battery) => {
-                            if batteries >= factory.quantity {
-                                println!("Drop {} batteries at ({} {})", factory.quantity, fx, fy);
-                                agent.state[0].1 -= factory.quantity;
-                                factory.quantity = 0;
-                                factory.needed_tool = None;
-                            }
-                        }
-                        Some(ToolType::WeldingArm) => {
-                            if welding_arms >= factory.quantity {
-                                println!(
-                                    "Drop {} welding arms at ({} {})",
-                                    factory.quantity, fx, fy
-                                );
-                                agent.state[1].1 -= factory.quantity;
-                                factory.quantity = 0;
-                                factory.needed_tool = None;
-                            }
-                        }
-                        Some(ToolType::SuctionPump) => {
-                            if pumps >= factory.quantity {
-                                println!(
-                                    "Drop {} suction pumps at ({} {})",
-                                    factory.quantity, fx, fy
-                                );
-                                agent.state[2].1 -= factory.quantity;
-                                factory.quantity = 0;
-                                factory.needed_tool = None;
-                            }
-                        }
-                        Some(ToolType::CoolingDevice) => {
-                            if cooling_devices >= factory.quantity {
-                                println!(
-                                    "Drop {} cooling devices at ({} {})",
-                                    factory.quantity, fx, fy
-                                );
-                                agent.state[3].1 -= factory.quantity;
-                                factory.quantity = 0;
-                                factory.needed_tool = None;
-                            }
-                        }
-                        Some(ToolType::PneumaticArm) => {
-                            if pneumatic_arms >= factory.quantity {
-                                println!(
-                                    "Drop {} pneumatic arms at ({} {})",
-                                    factory.quantity, fx, fy
-                                );
-                                agent.state[4].1 -= factory.quantity;
-                                factory.quantity = 0;
-                                factory.needed_tool = None;
-                            }
-                        }
-                        None => {}
+                }
+                for (tool_type, quantity) in &mut agent.state {
+                    if *tool_type == tool {
+                        *quantity += 1;
                     }
-                    if agent.destination_queue.contains(&(fx, fy)) {
-                        let mut idx = 0;
-                        for (dx, dy) in &agent.destination_queue {
-                            if *dx == fx && *dy == fy {
-                                break;
-                            }
-                            idx += 1;
-                        }
-                        agent.destination_queue.remove(idx);
+                }
+            }
+            TrajectoryEvent::Drop {
+                x,
+                y,
+                tool,
+                quantity,
+            } => {
+                println!("Drop {} {:?} at ({} {})", quantity, tool, x, y);
+                for (tool_type, held) in &mut agent.state {
+                    if *tool_type == tool {
+                        *held -= quantity;
                     }
-                    for fact in &mut agent.requisitions {
-                        let fxx = fact.x;
-                        let fyy = fact.y;
-                        if fx == fxx && fy == fyy {
-                            fact.needed_tool = None;
-                            fact.quantity = 0;
-                        }
+                }
+            }
+            TrajectoryEvent::Deliver { x, y } => {
+                for fact in &mut agent.requisitions {
+                    if fact.x == x && fact.y == y {
+                        fact.needed_tool = None;
+                        fact.quantity = 0;
                     }
                 }
-                None => {}
+            }
+            TrajectoryEvent::Terrain { x, y, terrain } => {
+                let mut cell = query_cell.get_mut(board.cells[x][y]).unwrap();
+                cell.terrain = terrain;
+            }
+            TrajectoryEvent::Tool { x, y, tool } => {
+                spawn_tool(&mut commands, &asset_server, &layout, (x, y), tool);
+                let mut cell = query_cell.get_mut(board.cells[x][y]).unwrap();
+                cell.tool = Some(tool);
             }
         }
     }
@@ -0,0 +1,101 @@
+use crate::grid::{GridCoord, GridLayout};
+use crate::terrain::Terrain;
+use bevy::prelude::*;
+
+// Quanto tempo um passo leva na tela: `seconds_per_cost` vezes o custo do
+// terreno de destino, então um passo no deserto demora 20 vezes um na grama.
+// Só muda o desenho; a simulação continua andando de célula em célula.
+pub struct Animation {
+    pub enabled: bool,
+    pub seconds_per_cost: f32,
+}
+
+impl Animation {
+    pub fn duration(&self, terrain: Terrain) -> f32 {
+        if !self.enabled || !terrain.passable() {
+            return 0.0;
+        }
+        self.seconds_per_cost * terrain.cost() as f32
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            seconds_per_cost: 0.02,
+        }
+    }
+}
+
+// Passo em andamento do agente, em células. Fica em coordenadas do tabuleiro, e
+// não do mundo, para continuar certo quando `resize_layout` refaz o layout.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Motion {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl Motion {
+    pub fn at(position: (usize, usize)) -> Self {
+        Self {
+            from: position,
+            to: position,
+            elapsed: 0.0,
+            duration: 0.0,
+        }
+    }
+
+    pub fn start(&mut self, from: (usize, usize), to: (usize, usize), duration: f32) {
+        self.from = from;
+        self.to = to;
+        self.elapsed = 0.0;
+        self.duration = duration;
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        (self.elapsed / self.duration).min(1.0)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    pub fn position(&self, layout: &GridLayout) -> Vec2 {
+        let from = layout.center(GridCoord::new(self.from.0, self.from.1));
+        let to = layout.center(GridCoord::new(self.to.0, self.to.1));
+        from.lerp(to, self.progress())
+    }
+}
+
+// Os ticks esperam o passo anterior terminar de ser desenhado.
+pub fn motion_finished(query: &Query<&Motion>) -> bool {
+    query.iter().all(|motion| motion.finished())
+}
+
+pub fn animate_agent(
+    time: Res<Time>,
+    layout: Res<GridLayout>,
+    mut query: Query<(&mut Motion, &mut Transform)>,
+) {
+    for (mut motion, mut transform) in query.iter_mut() {
+        if !motion.finished() {
+            motion.elapsed += time.delta_seconds();
+        }
+        let position = motion.position(&layout);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+// M liga e desliga a animação; desligada, o agente pula direto para a célula.
+pub fn control_animation(keys: Res<Input<KeyCode>>, mut animation: ResMut<Animation>) {
+    if keys.just_pressed(KeyCode::M) {
+        animation.enabled = !animation.enabled;
+    }
+}
@@ -0,0 +1,209 @@
+use crate::movement::Movement;
+use crate::search::SearchStats;
+use crate::terrain::Terrain;
+use priority_queue::PriorityQueue;
+use std::time::{Duration, Instant};
+
+const INF: i64 = i64::MAX / 4;
+
+// Quanto o ARA* pode trabalhar em um tick antes de devolver o controle ao Bevy.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Budget {
+    Millis(u64),
+    Expansions(usize),
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget::Expansions(250)
+    }
+}
+
+// ARA* (Likhachev, Gordon e Thrun): roda A* ponderado com um epsilon alto, publica a
+// solução e vai diminuindo o epsilon, reaproveitando os valores g da rodada anterior.
+// A busca parte do objetivo em direção ao agente, assim toda célula alcançada sabe
+// quanto falta até o objetivo e o caminho pode ser refeito a partir de onde o agente
+// estiver quando uma solução melhor aparecer.
+pub struct AraStar {
+    width: i32,
+    height: i32,
+    goal: (i32, i32),
+    origin: (i32, i32),
+    movement: Movement,
+    // Epsilon em milésimos, para as prioridades continuarem inteiras.
+    epsilon: i64,
+    step: i64,
+    g: Vec<i64>,
+    parent: Vec<Option<(i32, i32)>>,
+    closed: Vec<bool>,
+    open: PriorityQueue<(i32, i32), i64>,
+    incons: Vec<(i32, i32)>,
+    finished: bool,
+}
+
+impl AraStar {
+    pub fn new(
+        origin: (i32, i32),
+        goal: (i32, i32),
+        width: i32,
+        height: i32,
+        movement: Movement,
+        epsilon: f32,
+        step: f32,
+    ) -> Self {
+        let size = (width * height) as usize;
+        let mut ara = Self {
+            width,
+            height,
+            goal,
+            origin,
+            movement,
+            epsilon: (epsilon.max(1.0) as f64 * 1000.0).round() as i64,
+            step: (step.max(0.001) as f64 * 1000.0).round() as i64,
+            g: vec![INF; size],
+            parent: vec![None; size],
+            closed: vec![false; size],
+            open: PriorityQueue::new(),
+            incons: vec![],
+            finished: false,
+        };
+        let id = ara.id(goal);
+        ara.g[id] = 0;
+        ara.open.push(goal, -ara.fvalue(goal));
+        ara
+    }
+
+    pub fn goal(&self) -> (i32, i32) {
+        self.goal
+    }
+
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn epsilon(&self) -> f32 {
+        self.epsilon as f32 / 1000.0
+    }
+
+    fn id(&self, (x, y): (i32, i32)) -> usize {
+        (x * self.height + y) as usize
+    }
+
+    fn fvalue(&self, node: (i32, i32)) -> i64 {
+        let g = self.g[self.id(node)];
+        if g >= INF {
+            return INF;
+        }
+        g * 1000 + self.epsilon * self.movement.heuristic(node, self.origin) as i64
+    }
+
+    fn exhausted(&self, started: Instant, used: usize, budget: Budget) -> bool {
+        match budget {
+            Budget::Millis(ms) => started.elapsed() >= Duration::from_millis(ms),
+            Budget::Expansions(limit) => used >= limit,
+        }
+    }
+
+    // Continua a busca até acabar o orçamento do tick. Retorna true se uma solução
+    // nova (com epsilon menor que a anterior) foi publicada nesta chamada.
+    pub fn improve<F>(&mut self, terrain: F, budget: Budget, stats: &mut SearchStats) -> bool
+    where
+        F: Fn(i32, i32) -> Terrain,
+    {
+        let started = Instant::now();
+        let mut used = 0;
+        let mut published = false;
+        while !self.finished {
+            let top = self.open.peek().map(|(_, priority)| -*priority);
+            if top.is_none_or(|top| self.fvalue(self.origin) <= top) {
+                let origin_id = self.id(self.origin);
+                if self.g[origin_id] >= INF {
+                    println!(
+                        "\nDistance between ({}, {}) and ({}, {}): -",
+                        self.origin.0, self.origin.1, self.goal.0, self.goal.1
+                    );
+                    self.finished = true;
+                    break;
+                }
+                println!("\nAnytime solution with epsilon {}", self.epsilon());
+                println!(
+                    "\nDistance between ({}, {}) and ({}, {}): {}",
+                    self.origin.0, self.origin.1, self.goal.0, self.goal.1, self.g[origin_id]
+                );
+                published = true;
+                if self.epsilon <= 1000 {
+                    self.finished = true;
+                    break;
+                }
+                self.epsilon = (self.epsilon - self.step).max(1000);
+                for node in std::mem::take(&mut self.incons) {
+                    self.open.push(node, 0);
+                }
+                let nodes: Vec<(i32, i32)> = self.open.iter().map(|(node, _)| *node).collect();
+                for node in nodes {
+                    let f = self.fvalue(node);
+                    self.open.change_priority(&node, -f);
+                }
+                self.closed.iter_mut().for_each(|closed| *closed = false);
+                continue;
+            }
+            if self.exhausted(started, used, budget) {
+                break;
+            }
+
+            let (current, _) = self.open.pop().unwrap();
+            let current_id = self.id(current);
+            self.closed[current_id] = true;
+            used += 1;
+            stats.expand(self.open.len() + 1);
+            // Na busca de trás para frente o vizinho `previous` chega em `current`
+            // pagando o terreno de `current`.
+            let cost = terrain(current.0, current.1);
+            for &(mx, my) in self.movement.offsets() {
+                let previous = (current.0 + mx, current.1 + my);
+                if !self
+                    .movement
+                    .can_move(current, (mx, my), self.width, self.height, &terrain)
+                {
+                    continue;
+                }
+                let previous_id = self.id(previous);
+                let n_cost = self.g[current_id] + self.movement.step_cost((mx, my), cost) as i64;
+                if n_cost >= self.g[previous_id] {
+                    continue;
+                }
+                self.g[previous_id] = n_cost;
+                self.parent[previous_id] = Some(current);
+                if self.closed[previous_id] {
+                    if !self.incons.contains(&previous) {
+                        self.incons.push(previous);
+                    }
+                } else {
+                    let f = self.fvalue(previous);
+                    self.open.push(previous, -f);
+                }
+            }
+        }
+        published
+    }
+
+    // Segue os ponteiros em direção ao objetivo a partir de `from`. Os valores g só
+    // diminuem, então os ponteiros nunca formam ciclos.
+    pub fn path_from(&self, from: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+        if from == self.goal || self.g[self.id(from)] >= INF {
+            return None;
+        }
+        let mut moves: Vec<(i32, i32)> = vec![];
+        let mut current = from;
+        while let Some(next) = self.parent[self.id(current)] {
+            moves.push((next.0 - current.0, next.1 - current.1));
+            current = next;
+        }
+        println!("Path: {:?}", moves);
+        Some(moves)
+    }
+}
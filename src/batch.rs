@@ -0,0 +1,189 @@
+use crate::params::Params;
+use crate::planner::Planner;
+use crate::sim::{RunSummary, Simulation};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+// Limite de ticks de uma execução do lote; as que não terminam saem com
+// `finished` falso em vez de travar o lote.
+pub const MAX_TICKS: usize = 1_000_000;
+
+// Um planejador com os fatores da busca, com o nome dos exemplos.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Strategy {
+    pub name: &'static str,
+    pub planner: Planner,
+    pub h_factor: i32,
+    pub g_factor: i32,
+}
+
+impl Strategy {
+    pub const fn new(name: &'static str, planner: Planner, h_factor: i32, g_factor: i32) -> Self {
+        Self {
+            name,
+            planner,
+            h_factor,
+            g_factor,
+        }
+    }
+
+    // O cenário com as quantidades de `Params::for_scenario`, como nos exemplos.
+    pub fn params(&self, input_idx: usize) -> Params {
+        Params {
+            planner: self.planner,
+            h_factor: self.h_factor,
+            g_factor: self.g_factor,
+            ..Params::for_scenario(input_idx)
+        }
+    }
+}
+
+// Os planejadores que rodam sem janela; neblina, eventos e ARA* ficam de fora
+// como em `Simulation`. O IDA* também, porque uma execução nos mapas de 42x42
+// leva minutos.
+pub const STRATEGIES: [Strategy; 8] = [
+    Strategy::new("estrela", Planner::AStar, 1, 1),
+    Strategy::new("guloso", Planner::AStar, 1, 0),
+    Strategy::new("uniforme", Planner::AStar, 0, 1),
+    Strategy::new("jps", Planner::JumpPoint, 1, 1),
+    Strategy::new("ponderado", Planner::Weighted, 1, 1),
+    Strategy::new("bidirecional", Planner::Bidirectional, 1, 1),
+    Strategy::new("dstar", Planner::DStarLite, 1, 1),
+    Strategy::new("hpa", Planner::Hierarchical, 1, 1),
+];
+
+pub fn strategy(name: &str) -> Option<Strategy> {
+    STRATEGIES.iter().find(|s| s.name == name).copied()
+}
+
+// Uma execução do lote: cenário de inputs/, estratégia e semente.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RunSpec {
+    pub scenario: usize,
+    pub strategy: Strategy,
+    pub repetition: usize,
+    pub seed: u64,
+}
+
+// Semente de uma repetição, tirada só da semente base, do cenário e da
+// repetição (splitmix64). Não depende da ordem nem da thread que roda a
+// execução, e todas as estratégias de um cenário recebem a mesma semente, o que
+// deixa as comparações pareadas.
+pub fn run_seed(base_seed: u64, scenario: usize, repetition: usize) -> u64 {
+    let mut z = base_seed
+        .wrapping_add((scenario as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .wrapping_add((repetition as u64).wrapping_mul(0xD1B5_4A32_D192_ED03));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// Todas as combinações, em ordem de cenário, repetição e estratégia.
+pub fn plan(
+    scenarios: &[usize],
+    strategies: &[Strategy],
+    repetitions: usize,
+    base_seed: u64,
+) -> Vec<RunSpec> {
+    let mut specs = vec![];
+    for &scenario in scenarios {
+        for repetition in 0..repetitions {
+            let seed = run_seed(base_seed, scenario, repetition);
+            for &strategy in strategies {
+                specs.push(RunSpec {
+                    scenario,
+                    strategy,
+                    repetition,
+                    seed,
+                });
+            }
+        }
+    }
+    specs
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RunResult {
+    pub spec: RunSpec,
+    pub summary: RunSummary,
+    pub runtime: Duration,
+}
+
+pub fn run_one(spec: &RunSpec, width: usize, height: usize) -> RunResult {
+    let start = Instant::now();
+    let params = spec.strategy.params(spec.scenario);
+    let summary = Simulation::scenario(width, height, params, spec.seed)
+        .unwrap_or_else(|error| panic!("{}: {}", spec.strategy.name, error))
+        .run(MAX_TICKS);
+    RunResult {
+        spec: *spec,
+        summary,
+        runtime: start.elapsed(),
+    }
+}
+
+// Roda as execuções em `threads` threads (0 usa todos os núcleos) e devolve os
+// resultados na ordem de `specs`, seja qual for a ordem em que terminaram.
+// `progress` recebe (prontas, total) a cada execução que termina, de qualquer
+// thread.
+pub fn run_batch<F>(
+    specs: &[RunSpec],
+    width: usize,
+    height: usize,
+    threads: usize,
+    progress: F,
+) -> Vec<RunResult>
+where
+    F: Fn(usize, usize) + Sync,
+{
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("Something went wrong");
+    let done = AtomicUsize::new(0);
+    pool.install(|| {
+        specs
+            .par_iter()
+            .map(|spec| {
+                let result = run_one(spec, width, height);
+                progress(done.fetch_add(1, Ordering::SeqCst) + 1, specs.len());
+                result
+            })
+            .collect()
+    })
+}
+
+pub const CSV_HEADER: &str = "scenario,strategy,repetition,seed,finished,ticks,cost,expansions,peak_open,random_moves,random_moves_cost,runtime_us";
+
+impl RunResult {
+    pub fn csv_row(&self) -> String {
+        let summary = &self.summary;
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.spec.scenario,
+            self.spec.strategy.name,
+            self.spec.repetition,
+            self.spec.seed,
+            summary.finished,
+            summary.ticks,
+            summary.cost,
+            summary.expansions,
+            summary.peak_open,
+            summary.random_moves,
+            summary.random_moves_cost,
+            self.runtime.as_micros()
+        )
+    }
+}
+
+pub fn to_csv(results: &[RunResult]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for result in results {
+        csv += &result.csv_row();
+        csv.push('\n');
+    }
+    csv
+}
@@ -0,0 +1,63 @@
+use crate::board::Board;
+use crate::cell::Cell;
+use crate::terrain::Terrain;
+use bevy::prelude::*;
+
+pub struct Belief {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Vec<Option<Terrain>>>,
+}
+
+impl Belief {
+    pub fn new(width: usize, height: usize) -> Self {
+        let cells = vec![vec![None; height]; width];
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn known(&self, x: usize, y: usize) -> bool {
+        self.cells[x][y].is_some()
+    }
+
+    // Células desconhecidas são tratadas como grama: o agente planeja de forma
+    // otimista e replaneja quando o sensor revela algo pior.
+    pub fn terrain(&self, x: usize, y: usize) -> Terrain {
+        self.cells[x][y].unwrap_or(Terrain::Grass)
+    }
+
+    pub fn observe(
+        &mut self,
+        board: &Res<Board>,
+        ax: i32,
+        ay: i32,
+        r: i32,
+        query_cell: &Query<&mut Cell>,
+    ) -> Vec<(usize, usize)> {
+        let mut discovered: Vec<(usize, usize)> = vec![];
+        let width = self.width as i32;
+        let height = self.height as i32;
+        for x in ax - r..=ax + r {
+            for y in ay - r..=ay + r {
+                if x >= 0 && x < width && y >= 0 && y < height {
+                    let (x, y) = (x as usize, y as usize);
+                    let cell = query_cell.get(board.cells[x][y]).unwrap();
+                    if self.cells[x][y] != Some(cell.terrain) {
+                        self.cells[x][y] = Some(cell.terrain);
+                        discovered.push((x, y));
+                    }
+                }
+            }
+        }
+        discovered
+    }
+}
+
+impl Default for Belief {
+    fn default() -> Self {
+        Self::new(42, 42)
+    }
+}
@@ -0,0 +1,153 @@
+use crate::movement::Movement;
+use crate::search::{print_distance, SearchStats};
+use crate::terrain::Terrain;
+use priority_queue::PriorityQueue;
+use std::collections::HashMap;
+
+// Busca bidirecional: uma frente parte do agente e outra do objetivo, sempre
+// expandindo a que tem a menor chave. Com `heuristic` ligado as duas usam o
+// potencial médio (h(v, objetivo) - h(v, início)) / 2, que mantém a condição de
+// parada do Dijkstra bidirecional. As chaves são guardadas dobradas para não
+// perder a divisão por 2.
+#[allow(clippy::too_many_arguments)]
+pub fn bidirectional_search<F>(
+    (ax, ay): (i32, i32),
+    (dx, dy): (i32, i32),
+    width: i32,
+    height: i32,
+    terrain: F,
+    movement: &Movement,
+    heuristic: bool,
+    stats: &mut SearchStats,
+) -> Option<Vec<(i32, i32)>>
+where
+    F: Fn(i32, i32) -> Terrain,
+{
+    let start = (ax, ay);
+    let goal = (dx, dy);
+    let potential = |node: (i32, i32)| {
+        if heuristic {
+            movement.heuristic(node, goal) - movement.heuristic(node, start)
+        } else {
+            0
+        }
+    };
+    let forward_key = |g: i32, node: (i32, i32)| 2 * g + potential(node) - potential(start);
+    let backward_key = |g: i32, node: (i32, i32)| 2 * g + potential(goal) - potential(node);
+
+    let mut forward = PriorityQueue::new();
+    let mut backward = PriorityQueue::new();
+    let mut forward_distance: HashMap<(i32, i32), i32> = HashMap::new();
+    let mut backward_distance: HashMap<(i32, i32), i32> = HashMap::new();
+    let mut forward_parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut backward_parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut forward_closed: HashMap<(i32, i32), bool> = HashMap::new();
+    let mut backward_closed: HashMap<(i32, i32), bool> = HashMap::new();
+    let mut best: Option<(i32, (i32, i32))> = None;
+
+    println!(
+        "\nBidirectional search from ({}, {}) to ({}, {})",
+        ax, ay, dx, dy
+    );
+    forward_distance.insert(start, 0);
+    backward_distance.insert(goal, 0);
+    forward.push(start, -forward_key(0, start));
+    if terrain(dx, dy).passable() {
+        backward.push(goal, -backward_key(0, goal));
+    }
+    if start == goal {
+        best = Some((0, start));
+    }
+    while let (Some((_, f)), Some((_, b))) = (forward.peek(), backward.peek()) {
+        let (forward_top, backward_top) = (-*f, -*b);
+        if let Some((cost, _)) = best {
+            if forward_top + backward_top >= 2 * cost + potential(goal) - potential(start) {
+                break;
+            }
+        }
+        stats.expand(forward.len() + backward.len());
+        if forward_top <= backward_top {
+            let (current, _) = forward.pop().unwrap();
+            forward_closed.insert(current, true);
+            stats.close(current);
+            for &(mx, my) in movement.offsets() {
+                if !movement.can_move(current, (mx, my), width, height, &terrain) {
+                    continue;
+                }
+                let next = (current.0 + mx, current.1 + my);
+                if forward_closed.contains_key(&next) {
+                    continue;
+                }
+                let n_distance = forward_distance[&current]
+                    + movement.step_cost((mx, my), terrain(next.0, next.1)) as i32;
+                if forward_distance.contains_key(&next) && n_distance >= forward_distance[&next] {
+                    continue;
+                }
+                forward_distance.insert(next, n_distance);
+                forward_parent.insert(next, current);
+                forward.push(next, -forward_key(n_distance, next));
+                stats.opened.push(next);
+                if let Some(rest) = backward_distance.get(&next) {
+                    if best.is_none_or(|(cost, _)| n_distance + rest < cost) {
+                        best = Some((n_distance + rest, next));
+                    }
+                }
+            }
+        } else {
+            // Na volta a aresta percorrida é previous -> current, que paga o
+            // terreno de `current`.
+            let (current, _) = backward.pop().unwrap();
+            backward_closed.insert(current, true);
+            stats.close(current);
+            for &(mx, my) in movement.offsets() {
+                if !movement.can_move(current, (mx, my), width, height, &terrain) {
+                    continue;
+                }
+                let previous = (current.0 + mx, current.1 + my);
+                if backward_closed.contains_key(&previous) {
+                    continue;
+                }
+                let n_distance = backward_distance[&current]
+                    + movement.step_cost((mx, my), terrain(current.0, current.1)) as i32;
+                if backward_distance.contains_key(&previous)
+                    && n_distance >= backward_distance[&previous]
+                {
+                    continue;
+                }
+                backward_distance.insert(previous, n_distance);
+                backward_parent.insert(previous, current);
+                backward.push(previous, -backward_key(n_distance, previous));
+                stats.opened.push(previous);
+                if let Some(rest) = forward_distance.get(&previous) {
+                    if best.is_none_or(|(cost, _)| n_distance + rest < cost) {
+                        best = Some((n_distance + rest, previous));
+                    }
+                }
+            }
+        }
+    }
+
+    let meeting = match best {
+        Some((_, meeting)) if start != goal => meeting,
+        _ => {
+            print_distance(start, goal, None, movement, &terrain);
+            return None;
+        }
+    };
+
+    let mut moves: Vec<(i32, i32)> = vec![];
+    let mut current = meeting;
+    while let Some(previous) = forward_parent.get(&current) {
+        moves.push((current.0 - previous.0, current.1 - previous.1));
+        current = *previous;
+    }
+    moves.reverse();
+    let mut current = meeting;
+    while let Some(next) = backward_parent.get(&current) {
+        moves.push((next.0 - current.0, next.1 - current.1));
+        current = *next;
+    }
+    print_distance(start, goal, Some(&moves), movement, &terrain);
+    println!("Path: {:?}", moves);
+    Some(moves)
+}
@@ -1,8 +1,12 @@
 use crate::cell::Cell;
+use crate::grid::{GridCoord, GridLayout};
+use crate::params::Params;
 use crate::terrain::Terrain;
 use bevy::prelude::*;
 use std::fs;
+use std::path::Path as FilePath;
 
+// `cells[x][y]`, com x < width e y < height; ver `GridCoord`.
 pub struct Board {
     pub width: usize,
     pub height: usize,
@@ -11,13 +15,17 @@ pub struct Board {
 
 impl Board {
     pub fn new(width: usize, height: usize) -> Self {
-        let cells = vec![vec![Entity::from_raw(0); width]; height];
+        let cells = vec![vec![Entity::from_raw(0); height]; width];
         Self {
             width,
             height,
             cells,
         }
     }
+
+    pub fn entity(&self, coord: GridCoord) -> Entity {
+        self.cells[coord.x][coord.y]
+    }
 }
 
 impl Default for Board {
@@ -26,10 +34,8 @@ impl Default for Board {
     }
 }
 
-fn read_terrain() -> Vec<Vec<Terrain>> {
+pub fn parse_field(contents: &str) -> Vec<Vec<Terrain>> {
     let mut data: Vec<Vec<Terrain>> = Vec::<Vec<Terrain>>::default();
-    let field_path = "fields/field.txt".to_string();
-    let contents = fs::read_to_string(field_path).expect("Something went wrong");
     for line in contents.split('\n') {
         let line = line.trim();
         let values: Vec<&str> = line.split_whitespace().collect();
@@ -50,51 +56,111 @@ fn read_terrain() -> Vec<Vec<Terrain>> {
     data
 }
 
-pub fn setup_board(mut commands: Commands, windows: Res<Windows>, mut board: ResMut<Board>) {
-    let window = windows.primary();
-    let border_width = 2.0;
-    let cell_width =
-        (window.width() - border_width * (board.width - 1) as f32) / (board.width as f32);
-    let cell_height =
-        (window.height() - border_width * (board.height - 1) as f32) / (board.height as f32);
+pub fn read_terrain() -> Vec<Vec<Terrain>> {
+    let field_path = "fields/field.txt".to_string();
+    let contents = fs::read_to_string(field_path).expect("Something went wrong");
+    parse_field(&contents)
+}
+
+// O mapa do cenário `idx`: fields/field_<idx>.txt, gravado pelo editor, ou o mapa
+// comum fields/field.txt quando o cenário não tem um próprio.
+pub fn read_field(idx: usize) -> Vec<Vec<Terrain>> {
+    let field_path = format!("fields/field_{}.txt", idx);
+    if !FilePath::new(&field_path).exists() {
+        return read_terrain();
+    }
+    let contents = fs::read_to_string(field_path).expect("Something went wrong");
+    parse_field(&contents)
+}
+
+// Passa o mapa do arquivo (linhas de cima para baixo) para `[x][y]`, o mesmo
+// índice de `Board::cells` e dos planejadores.
+pub fn terrain_grid(field: &[Vec<Terrain>], width: usize, height: usize) -> Vec<Vec<Terrain>> {
+    let mut data = vec![vec![Terrain::Grass; height]; width];
+    for (x, column) in data.iter_mut().enumerate() {
+        for (y, value) in column.iter_mut().enumerate() {
+            let (row, col) = GridCoord::new(x, y).field_index(height);
+            *value = field[row][col];
+        }
+    }
+    data
+}
+
+// Terreno indexado do mesmo jeito que `Board::cells`, para quem precisa do mapa
+// sem passar pelas entidades do Bevy.
+pub fn board_terrain(board: &Board) -> Vec<Vec<Terrain>> {
+    terrain_grid(&read_terrain(), board.width, board.height)
+}
 
-    let terrain = read_terrain();
+pub fn setup_board(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    mut board: ResMut<Board>,
+    params: Res<Params>,
+) {
+    let layout = GridLayout::from_window(windows.primary(), &board);
+    let terrain = terrain_grid(&read_field(params.input_idx), board.width, board.height);
 
-    for xx in 0..board.height {
-        for yy in 0..board.width {
-            let x = xx as f32;
-            let y = yy as f32;
-            let cx = -window.height() / 2. + cell_height * x + border_width * x + cell_height / 2.;
-            let cy = -window.width() / 2. + cell_width * y + border_width * y + cell_width / 2.;
-            let cell_x = board.height - yy - 1;
-            let cell_y = xx;
+    for x in 0..board.width {
+        for y in 0..board.height {
+            let center = layout.center(GridCoord::new(x, y));
             let entity = commands
                 .spawn_bundle(SpriteBundle {
-                    transform: Transform::from_xyz(cx, cy, 1.0),
+                    transform: Transform::from_translation(center.extend(1.0)),
                     sprite: Sprite {
                         color: Color::rgb(1., 1., 1.),
-                        custom_size: Some(Vec2::new(cell_width, cell_height)),
+                        custom_size: Some(layout.cell_size()),
                         ..default()
                     },
                     ..default()
                 })
-                .insert(Cell::new(terrain[cell_x][cell_y], None, None))
+                .insert(Cell::new(terrain[x][y], None, None))
                 .id();
-            board.cells[xx][yy] = entity;
+            board.cells[x][y] = entity;
         }
     }
+    commands.insert_resource(layout);
+}
+
+pub fn terrain_color(terrain: Terrain) -> (f32, f32, f32) {
+    match terrain {
+        Terrain::Grass => (0.569, 0.816, 0.306),
+        Terrain::Mountain => (0.576, 0.541, 0.325),
+        Terrain::Swamp => (0.325, 0.55, 0.827),
+        Terrain::Desert => (0.89, 0.42, 0.04),
+        Terrain::Obstacle => (0.0, 0.0, 0.0),
+    }
 }
 
 pub fn color_cells(mut query_cell: Query<(&Cell, &mut Sprite), Changed<Cell>>) {
     for (cell, mut sprite) in query_cell.iter_mut() {
-        let colors: (f32, f32, f32) = match cell.terrain {
-            Terrain::Grass => (0.569, 0.816, 0.306),
-            Terrain::Mountain => (0.576, 0.541, 0.325),
-            Terrain::Swamp => (0.325, 0.55, 0.827),
-            Terrain::Desert => (0.89, 0.42, 0.04),
-            Terrain::Obstacle => (0.0, 0.0, 0.0),
-        };
-        let (red, green, blue) = colors;
+        let (red, green, blue) = terrain_color(cell.terrain);
         sprite.color = Color::rgb(red, green, blue);
     }
 }
+
+// O inverso de `terrain_grid` + `parse_field`: volta para o texto de
+// fields/field.txt, com a primeira linha sendo o topo da tela.
+pub fn field_text(terrain: &[Vec<Terrain>]) -> String {
+    let width = terrain.len();
+    let height = terrain[0].len();
+    let mut text = String::new();
+    for row in 0..height {
+        let y = height - row - 1;
+        let values: Vec<String> = (0..width)
+            .map(|x| {
+                match terrain[x][y] {
+                    Terrain::Grass => "0",
+                    Terrain::Mountain => "1",
+                    Terrain::Swamp => "2",
+                    Terrain::Desert => "3",
+                    Terrain::Obstacle => "4",
+                }
+                .to_string()
+            })
+            .collect();
+        text += &values.join(" ");
+        text += "\n";
+    }
+    text
+}
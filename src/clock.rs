@@ -0,0 +1,122 @@
+use crate::agent::Agent;
+use crate::animation::{motion_finished, Motion};
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+
+// Relógio da simulação. Os sistemas que fazem o agente andar só rodam quando
+// `clock_tick` libera um tick. Teclas:
+//   Espaço pausa/continua, N avança um tick (pausado), + e - mudam a velocidade,
+//   E entra/sai do modo de expansões, setas esquerda/direita andam pelas expansões
+//   da última busca.
+pub struct SimulationClock {
+    pub paused: bool,
+    pub pending_steps: usize,
+    // Ticks por segundo. None roda um tick por quadro, como sempre foi.
+    pub speed: Option<f32>,
+    pub ticks: usize,
+    // Quantas expansões da última busca estão sendo mostradas no modo passo a passo.
+    pub expansion: Option<usize>,
+    elapsed: f32,
+}
+
+impl SimulationClock {
+    pub fn new() -> Self {
+        Self {
+            paused: false,
+            pending_steps: 0,
+            speed: None,
+            ticks: 0,
+            expansion: None,
+            elapsed: 0.0,
+        }
+    }
+
+    // Decide se este quadro roda um tick, dado o tempo desde o quadro anterior.
+    pub fn tick(&mut self, delta: f32) -> ShouldRun {
+        if self.paused {
+            if self.pending_steps == 0 {
+                return ShouldRun::No;
+            }
+            self.pending_steps -= 1;
+            self.ticks += 1;
+            return ShouldRun::Yes;
+        }
+        match self.speed {
+            None => {}
+            Some(speed) => {
+                self.elapsed += delta;
+                if self.elapsed < 1.0 / speed {
+                    return ShouldRun::No;
+                }
+                self.elapsed = 0.0;
+            }
+        }
+        self.ticks += 1;
+        ShouldRun::Yes
+    }
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn clock_tick(
+    time: Res<Time>,
+    mut clock: ResMut<SimulationClock>,
+    query: Query<&Motion>,
+) -> ShouldRun {
+    if !motion_finished(&query) {
+        return ShouldRun::No;
+    }
+    clock.tick(time.delta_seconds())
+}
+
+pub fn control_clock(
+    keys: Res<Input<KeyCode>>,
+    mut clock: ResMut<SimulationClock>,
+    query: Query<&Agent>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        clock.paused = !clock.paused;
+        clock.expansion = None;
+    }
+    if keys.just_pressed(KeyCode::N) && clock.paused && clock.expansion.is_none() {
+        clock.pending_steps += 1;
+    }
+    if keys.just_pressed(KeyCode::Equals) || keys.just_pressed(KeyCode::NumpadAdd) {
+        clock.speed = match clock.speed {
+            Some(speed) if speed < 32.0 => Some(speed * 2.0),
+            _ => None,
+        };
+    }
+    if keys.just_pressed(KeyCode::Minus) || keys.just_pressed(KeyCode::NumpadSubtract) {
+        clock.speed = match clock.speed {
+            Some(speed) => Some((speed / 2.0).max(0.5)),
+            None => Some(32.0),
+        };
+    }
+
+    let expansions = match query.get_single() {
+        Ok(agent) => agent.last_search().closed.len(),
+        Err(_) => 0,
+    };
+    if keys.just_pressed(KeyCode::E) {
+        clock.expansion = match clock.expansion {
+            Some(_) => None,
+            None => {
+                clock.paused = true;
+                Some(0)
+            }
+        };
+    }
+    if let Some(expansion) = clock.expansion {
+        if keys.just_pressed(KeyCode::Right) {
+            clock.expansion = Some((expansion + 1).min(expansions));
+        }
+        if keys.just_pressed(KeyCode::Left) {
+            clock.expansion = Some(expansion.saturating_sub(1));
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::ara::AraStar;
+use crate::bidirectional::bidirectional_search;
+use crate::dstar::DStarLite;
+use crate::factory::Factory;
+use crate::hpa::Hpa;
+use crate::ida::ida_star;
+use crate::jps::jump_point_search;
+use crate::movement::Movement;
+use crate::params::Params;
+use crate::planner::Planner;
+use crate::poi::{DestinationPolicy, PoiCache};
+use crate::search::{a_star, SearchStats};
+use crate::terrain::Terrain;
+use crate::tool::ToolType;
+use crate::weighted::weighted_a_star;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+
+// As regras do agente sem Bevy, usadas pelos sistemas de `agent` e pela
+// `Simulation`, para que as duas execuções tomem as mesmas decisões.
+
+pub const TOOL_TYPES: [ToolType; 5] = [
+    ToolType::Battery,
+    ToolType::WeldingArm,
+    ToolType::SuctionPump,
+    ToolType::CoolingDevice,
+    ToolType::PneumaticArm,
+];
+
+// Inventário vazio, na ordem de `TOOL_TYPES`.
+pub fn empty_inventory() -> Vec<(ToolType, usize)> {
+    TOOL_TYPES.iter().map(|tool| (*tool, 0)).collect()
+}
+
+pub fn tool_index(tool: ToolType) -> usize {
+    TOOL_TYPES.iter().position(|t| *t == tool).unwrap()
+}
+
+// Alguma fábrica ainda pede essa ferramenta?
+pub fn requested(tool: ToolType, requisitions: &[Factory]) -> bool {
+    requisitions
+        .iter()
+        .any(|factory| factory.needed_tool == Some(tool))
+}
+
+// Enfileira as fábricas que já podem ser atendidas com o inventário e diz se
+// todas as requisições foram atendidas.
+pub fn check_requisitions(
+    state: &[(ToolType, usize)],
+    requisitions: &[Factory],
+    destinations: &mut Vec<(usize, usize)>,
+) -> bool {
+    let mut done = 0;
+    for factory in requisitions {
+        let position = (factory.x, factory.y);
+        if destinations.contains(&position) {
+            continue;
+        }
+        match factory.needed_tool {
+            Some(tool) => {
+                if state[tool_index(tool)].1 >= factory.quantity {
+                    destinations.push(position);
+                }
+            }
+            None => done += 1,
+        }
+    }
+    done == requisitions.len()
+}
+
+// Enfileira as ferramentas pedidas por alguma fábrica dentro do raio `r`.
+pub fn check_radius<F>(
+    (ax, ay): (i32, i32),
+    r: i32,
+    width: usize,
+    height: usize,
+    requisitions: &[Factory],
+    destinations: &mut Vec<(usize, usize)>,
+    tool_at: F,
+) where
+    F: Fn(usize, usize) -> Option<ToolType>,
+{
+    for x in ax - r..=ax + r {
+        for y in ay - r..=ay + r {
+            if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+                continue;
+            }
+            let position = (x as usize, y as usize);
+            if destinations.contains(&position) {
+                continue;
+            }
+            if let Some(tool) = tool_at(position.0, position.1) {
+                if requested(tool, requisitions) {
+                    destinations.push(position);
+                }
+            }
+        }
+    }
+}
+
+// Índice em `destinations` do próximo destino segundo a política.
+pub fn next_destination(
+    position: (usize, usize),
+    destinations: &[(usize, usize)],
+    params: &Params,
+    poi_cache: &mut PoiCache,
+) -> usize {
+    let position = (position.0 as i32, position.1 as i32);
+    let queue: Vec<(i32, i32)> = destinations
+        .iter()
+        .map(|(x, y)| (*x as i32, *y as i32))
+        .collect();
+    match params.destination {
+        DestinationPolicy::Heuristic => {
+            let mut min_idx = 0;
+            let mut min_distance = i32::MAX;
+            for (idx, destination) in queue.iter().enumerate() {
+                let distance = params.movement.heuristic(position, *destination);
+                if distance < min_distance {
+                    min_distance = distance;
+                    min_idx = idx;
+                }
+            }
+            min_idx
+        }
+        DestinationPolicy::Cached => {
+            let mut min_idx = 0;
+            let mut min_distance = i64::MAX;
+            for (idx, destination) in queue.iter().enumerate() {
+                if let Some(distance) = poi_cache.distance(position, *destination) {
+                    if distance < min_distance {
+                        min_distance = distance;
+                        min_idx = idx;
+                    }
+                }
+            }
+            min_idx
+        }
+        DestinationPolicy::Tour => poi_cache.tour(position, &queue)[0],
+    }
+}
+
+// Vale planejar até o destino? Só se ainda há ali uma ferramenta pedida ou uma
+// fábrica esperando entrega.
+pub fn should_find_path(
+    tool: Option<ToolType>,
+    factory: Option<Factory>,
+    requisitions: &[Factory],
+) -> bool {
+    match tool {
+        Some(tool) => requested(tool, requisitions),
+        None => matches!(factory, Some(f) if f.needed_tool.is_some()),
+    }
+}
+
+// Pisar numa fábrica: entrega o que ela pede se o agente tem o bastante, tira a
+// fábrica da fila e encerra a requisição do agente, com ou sem entrega. Devolve o
+// que foi entregue.
+pub fn visit_factory(
+    factory: &Factory,
+    state: &mut [(ToolType, usize)],
+    requisitions: &mut [Factory],
+    destinations: &mut Vec<(usize, usize)>,
+) -> Option<(ToolType, usize)> {
+    let (x, y) = (factory.x, factory.y);
+    let mut delivered = None;
+    if let Some(tool) = factory.needed_tool {
+        let held = &mut state[tool_index(tool)].1;
+        if *held >= factory.quantity {
+            *held -= factory.quantity;
+            delivered = Some((tool, factory.quantity));
+        }
+    }
+    if let Some(idx) = destinations.iter().position(|d| *d == (x, y)) {
+        destinations.remove(idx);
+    }
+    for requisition in requisitions {
+        if requisition.x == x && requisition.y == y {
+            requisition.needed_tool = None;
+            requisition.quantity = 0;
+        }
+    }
+    delivered
+}
+
+// Sorteia um passo do passeio aleatório, com peso inverso ao custo e sem voltar
+// pelo último movimento. `None` se o agente não tem para onde ir.
+pub fn random_step<F, R>(
+    (ax, ay): (i32, i32),
+    last_move: (i32, i32),
+    width: i32,
+    height: i32,
+    movement: &Movement,
+    terrain: F,
+    rng: &mut R,
+) -> Option<(i32, i32)>
+where
+    F: Fn(i32, i32) -> Terrain,
+    R: Rng,
+{
+    let offsets = movement.offsets();
+    let mut weights: Vec<f32> = vec![0.0; offsets.len()];
+    let mut has_option = false;
+    for (i, &(mx, my)) in offsets.iter().enumerate() {
+        if last_move == (mx, my) {
+            continue;
+        }
+        if !movement.can_move((ax, ay), (mx, my), width, height, &terrain) {
+            continue;
+        }
+        let step_cost = movement.step_cost((mx, my), terrain(ax + mx, ay + my));
+        weights[i] = 1.0 / step_cost as f32;
+        has_option = true;
+    }
+    if !has_option {
+        return None;
+    }
+    let dist = WeightedIndex::new(&weights).unwrap();
+    Some(offsets[dist.sample(rng)])
+}
+
+pub fn path_cost<F>(
+    start: (i32, i32),
+    moves: &[(i32, i32)],
+    movement: &Movement,
+    terrain: F,
+) -> usize
+where
+    F: Fn(i32, i32) -> Terrain,
+{
+    let (mut x, mut y) = start;
+    let mut cost = 0;
+    for &(mx, my) in moves {
+        x += mx;
+        y += my;
+        cost += movement.step_cost((mx, my), terrain(x, y));
+    }
+    cost
+}
+
+// O estado que os planejadores incrementais guardam entre uma busca e outra.
+#[derive(Default)]
+pub struct Planners {
+    pub dstar: Option<DStarLite>,
+    pub hpa: Option<Hpa>,
+    pub ara: Option<AraStar>,
+    // Clusters refeitos pela última atualização do HPA*.
+    pub rebuilt: usize,
+}
+
+impl Planners {
+    // Planeja de `start` até `goal` com o planejador de `params`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn plan<F>(
+        &mut self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        width: i32,
+        height: i32,
+        terrain: F,
+        params: &Params,
+        stats: &mut SearchStats,
+    ) -> Option<Vec<(i32, i32)>>
+    where
+        F: Fn(i32, i32) -> Terrain,
+    {
+        let movement = &params.movement;
+        match params.planner {
+            Planner::AStar => a_star(
+                start,
+                goal,
+                width,
+                height,
+                terrain,
+                movement,
+                params.g_factor,
+                params.h_factor,
+                stats,
+            ),
+            Planner::JumpPoint => jump_point_search(
+                start,
+                goal,
+                width,
+                height,
+                terrain,
+                movement,
+                params.g_factor,
+                params.h_factor,
+                stats,
+            ),
+            Planner::DStarLite => {
+                let mut dstar = match self.dstar.take() {
+                    Some(dstar) if dstar.goal() == goal => dstar,
+                    _ => DStarLite::new(start, goal, width, height, params.movement),
+                };
+                let path = dstar.replan(start, terrain, stats);
+                self.dstar = Some(dstar);
+                path
+            }
+            Planner::Hierarchical => {
+                let mut hpa = match self.hpa.take() {
+                    Some(mut hpa) => {
+                        self.rebuilt = hpa.update(&terrain);
+                        hpa
+                    }
+                    None => Hpa::new(width, height, params.cluster_size, params.movement, terrain),
+                };
+                let path = hpa.find_path(start, goal, stats);
+                self.hpa = Some(hpa);
+                path
+            }
+            Planner::Weighted => weighted_a_star(
+                start,
+                goal,
+                width,
+                height,
+                terrain,
+                movement,
+                params.epsilon,
+                stats,
+            ),
+            Planner::Bidirectional => bidirectional_search(
+                start,
+                goal,
+                width,
+                height,
+                terrain,
+                movement,
+                params.h_factor != 0,
+                stats,
+            ),
+            Planner::IterativeDeepening => ida_star(
+                start,
+                goal,
+                width,
+                height,
+                terrain,
+                movement,
+                params.ida_limit,
+                stats,
+            ),
+            Planner::Anytime => {
+                let mut ara = match self.ara.take() {
+                    Some(ara) if ara.goal() == goal && ara.origin() == start => ara,
+                    _ => AraStar::new(
+                        start,
+                        goal,
+                        width,
+                        height,
+                        params.movement,
+                        params.anytime_epsilon,
+                        params.anytime_step,
+                    ),
+                };
+                ara.improve(terrain, params.anytime_budget, stats);
+                let path = ara.path_from(start);
+                self.ara = Some(ara);
+                path
+            }
+        }
+    }
+
+    // O ARA* ainda não achou solução e continua no próximo tick.
+    pub fn searching(&self) -> bool {
+        matches!(&self.ara, Some(ara) if !ara.finished())
+    }
+}
@@ -0,0 +1,223 @@
+use crate::movement::Movement;
+use crate::search::{print_distance, valid, SearchStats};
+use crate::terrain::Terrain;
+use priority_queue::PriorityQueue;
+
+const INF: i64 = i64::MAX / 4;
+
+// D* Lite (Koenig & Likhachev): a busca parte do objetivo em direção ao agente,
+// então quando o agente anda ou o custo de alguma célula muda só os vértices
+// afetados são reavaliados, aproveitando o resto da busca anterior.
+pub struct DStarLite {
+    width: i32,
+    height: i32,
+    movement: Movement,
+    start: (i32, i32),
+    last: (i32, i32),
+    goal: (i32, i32),
+    km: i64,
+    g: Vec<i64>,
+    rhs: Vec<i64>,
+    terrain: Vec<Option<Terrain>>,
+    open: PriorityQueue<(i32, i32), (i64, i64)>,
+}
+
+impl DStarLite {
+    pub fn new(
+        start: (i32, i32),
+        goal: (i32, i32),
+        width: i32,
+        height: i32,
+        movement: Movement,
+    ) -> Self {
+        let size = (width * height) as usize;
+        let mut dstar = Self {
+            width,
+            height,
+            movement,
+            start,
+            last: start,
+            goal,
+            km: 0,
+            g: vec![INF; size],
+            rhs: vec![INF; size],
+            terrain: vec![None; size],
+            open: PriorityQueue::new(),
+        };
+        let id = dstar.id(goal);
+        dstar.rhs[id] = 0;
+        let key = dstar.calculate_key(goal);
+        dstar.open.push(goal, (-key.0, -key.1));
+        dstar
+    }
+
+    pub fn goal(&self) -> (i32, i32) {
+        self.goal
+    }
+
+    fn id(&self, (x, y): (i32, i32)) -> usize {
+        (x * self.height + y) as usize
+    }
+
+    fn terrain_at(&self, x: i32, y: i32) -> Terrain {
+        self.terrain[self.id((x, y))].unwrap_or(Terrain::Obstacle)
+    }
+
+    fn cost(&self, u: (i32, i32), s: (i32, i32)) -> i64 {
+        let step = (s.0 - u.0, s.1 - u.1);
+        let terrain = |x, y| self.terrain_at(x, y);
+        if !self
+            .movement
+            .can_move(u, step, self.width, self.height, terrain)
+        {
+            return INF;
+        }
+        self.movement.step_cost(step, self.terrain_at(s.0, s.1)) as i64
+    }
+
+    fn neighbors(&self, (x, y): (i32, i32)) -> Vec<(i32, i32)> {
+        let mut neighbors = vec![];
+        for &(mx, my) in self.movement.offsets() {
+            let (nx, ny) = (x + mx, y + my);
+            if valid(nx, ny, self.width, self.height) {
+                neighbors.push((nx, ny));
+            }
+        }
+        neighbors
+    }
+
+    fn calculate_key(&self, s: (i32, i32)) -> (i64, i64) {
+        let id = self.id(s);
+        let min = self.g[id].min(self.rhs[id]);
+        (
+            min + self.movement.heuristic(self.start, s) as i64 + self.km,
+            min,
+        )
+    }
+
+    fn update_vertex(&mut self, u: (i32, i32)) {
+        let id = self.id(u);
+        if u != self.goal {
+            let mut rhs = INF;
+            for s in self.neighbors(u) {
+                let cost = self.cost(u, s);
+                if cost < INF {
+                    rhs = rhs.min(cost + self.g[self.id(s)]);
+                }
+            }
+            self.rhs[id] = rhs.min(INF);
+        }
+        self.open.remove(&u);
+        if self.g[id] != self.rhs[id] {
+            let key = self.calculate_key(u);
+            self.open.push(u, (-key.0, -key.1));
+        }
+    }
+
+    fn compute_shortest_path(&mut self, stats: &mut SearchStats) {
+        while let Some((&u, &priority)) = self.open.peek() {
+            let k_old = (-priority.0, -priority.1);
+            let start_id = self.id(self.start);
+            if k_old >= self.calculate_key(self.start) && self.rhs[start_id] == self.g[start_id] {
+                break;
+            }
+            stats.expand(self.open.len());
+            let k_new = self.calculate_key(u);
+            let id = self.id(u);
+            if k_old < k_new {
+                self.open.change_priority(&u, (-k_new.0, -k_new.1));
+            } else if self.g[id] > self.rhs[id] {
+                self.g[id] = self.rhs[id];
+                self.open.remove(&u);
+                for s in self.neighbors(u) {
+                    self.update_vertex(s);
+                }
+            } else {
+                self.g[id] = INF;
+                for s in self.neighbors(u) {
+                    self.update_vertex(s);
+                }
+                self.update_vertex(u);
+            }
+        }
+    }
+
+    // Atualiza a posição do agente e os custos que mudaram desde a última chamada,
+    // refaz apenas a parte necessária da busca e devolve os movimentos até o objetivo.
+    pub fn replan<F>(
+        &mut self,
+        start: (i32, i32),
+        terrain: F,
+        stats: &mut SearchStats,
+    ) -> Option<Vec<(i32, i32)>>
+    where
+        F: Fn(i32, i32) -> Terrain,
+    {
+        if start != self.start {
+            self.km += self.movement.heuristic(self.last, start) as i64;
+            self.last = start;
+            self.start = start;
+        }
+
+        let mut changed: Vec<(i32, i32)> = vec![];
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let id = self.id((x, y));
+                let current = Some(terrain(x, y));
+                if self.terrain[id] != current {
+                    self.terrain[id] = current;
+                    changed.push((x, y));
+                }
+            }
+        }
+        for cell in changed {
+            for s in self.neighbors(cell) {
+                self.update_vertex(s);
+            }
+        }
+
+        println!(
+            "\nD* Lite from ({}, {}) to ({}, {})",
+            start.0, start.1, self.goal.0, self.goal.1
+        );
+        self.compute_shortest_path(stats);
+
+        let moves = if self.g[self.id(start)] >= INF || start == self.goal {
+            None
+        } else {
+            self.descend(start)
+        };
+        print_distance(start, self.goal, moves.as_deref(), &self.movement, &terrain);
+        if let Some(moves) = &moves {
+            println!("Path: {:?}", moves);
+        }
+        moves
+    }
+
+    // Desce pelos g até o objetivo, sempre pelo vizinho com menor custo da aresta
+    // mais g.
+    fn descend(&self, start: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+        let mut moves: Vec<(i32, i32)> = vec![];
+        let mut current = start;
+        while current != self.goal {
+            if moves.len() > (self.width * self.height) as usize {
+                return None;
+            }
+            let mut best: Option<((i32, i32), i64)> = None;
+            for s in self.neighbors(current) {
+                let cost = self.cost(current, s);
+                if cost >= INF || self.g[self.id(s)] >= INF {
+                    continue;
+                }
+                let total = cost + self.g[self.id(s)];
+                if best.is_none() || total < best.unwrap().1 {
+                    best = Some((s, total));
+                }
+            }
+            let (next, _) = best?;
+            moves.push((next.0 - current.0, next.1 - current.1));
+            current = next;
+        }
+        Some(moves)
+    }
+}
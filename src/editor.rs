@@ -0,0 +1,531 @@
+use crate::agent::{place_agent, spawn_agent};
+use crate::animation::{motion_finished, Motion};
+use crate::board::{field_text, Board};
+use crate::cell::Cell;
+use crate::clock::SimulationClock;
+use crate::decision::TOOL_TYPES;
+use crate::factory::*;
+use crate::grid::{GridCoord, GridLayout};
+use crate::params::Params;
+use crate::terrain::Terrain;
+use crate::tool::*;
+use crate::view::MainCamera;
+use crate::world_event::spawn_tool;
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+use std::fs;
+use std::path::Path as FilePath;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Brush {
+    Terrain(Terrain),
+    Tool,
+    Factory,
+    Agent,
+    Erase,
+}
+
+// Estado do modo editor. As ferramentas, fábricas e a posição do agente ficam
+// aqui na ordem em que foram colocadas, que é a ordem em que são gravadas.
+pub struct Editor {
+    pub active: bool,
+    pub brush: Brush,
+    pub tool_type: ToolType,
+    pub quantity: usize,
+    pub tools: Vec<Tool>,
+    pub factories: Vec<Factory>,
+    pub agent: Option<(usize, usize)>,
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Self {
+            active: true,
+            brush: Brush::Terrain(Terrain::Grass),
+            tool_type: ToolType::Battery,
+            quantity: 1,
+            tools: vec![],
+            factories: vec![],
+            agent: None,
+        }
+    }
+
+    pub fn occupied(&self, x: usize, y: usize) -> bool {
+        self.tools.iter().any(|t| t.x == x && t.y == y)
+            || self.factories.iter().any(|f| f.x == x && f.y == y)
+            || self.agent == Some((x, y))
+    }
+
+    // Se o pincel pode ser usado em (x, y), que tem o terreno `terrain`. As regras
+    // são as mesmas de `place_tools`, `place_factories` e `place_agent`, para que o
+    // cenário gravado volte igual.
+    pub fn can_paint(&self, brush: Brush, x: usize, y: usize, terrain: Terrain) -> bool {
+        let has_tool = self.tools.iter().any(|t| t.x == x && t.y == y);
+        let has_factory = self.factories.iter().any(|f| f.x == x && f.y == y);
+        match brush {
+            Brush::Terrain(new) => {
+                (!has_tool || new == Terrain::Grass)
+                    && (new.passable() || (!has_factory && self.agent != Some((x, y))))
+            }
+            Brush::Tool => terrain == Terrain::Grass && !self.occupied(x, y),
+            Brush::Factory | Brush::Agent => terrain.passable() && !self.occupied(x, y),
+            Brush::Erase => self.occupied(x, y),
+        }
+    }
+
+    // Quantidade de cada ferramenta, na ordem de `TOOL_TYPES`.
+    pub fn items_quantity(&self) -> Vec<(ToolType, usize)> {
+        TOOL_TYPES
+            .iter()
+            .map(|tool| {
+                let quantity = self
+                    .tools
+                    .iter()
+                    .filter(|t| t.tool_type == Some(*tool))
+                    .count();
+                (*tool, quantity)
+            })
+            .filter(|(_, quantity)| *quantity > 0)
+            .collect()
+    }
+
+    pub fn factories_needs(&self) -> Vec<(ToolType, usize)> {
+        self.factories
+            .iter()
+            .map(|f| (f.needed_tool.unwrap(), f.quantity))
+            .collect()
+    }
+
+    // inputs/tools_<idx>: `place_tools` lê as posições em sequência para cada tipo
+    // de `items_quantity`, então elas são agrupadas na mesma ordem.
+    pub fn tools_text(&self) -> String {
+        let mut text = String::new();
+        for (tool, _) in self.items_quantity() {
+            for t in self.tools.iter().filter(|t| t.tool_type == Some(tool)) {
+                text += &format!("{} {}\n", t.x, t.y);
+            }
+        }
+        text
+    }
+
+    pub fn factories_text(&self) -> String {
+        let mut text = String::new();
+        for f in &self.factories {
+            text += &format!("{} {}\n", f.x, f.y);
+        }
+        text
+    }
+
+    pub fn agent_text(&self) -> String {
+        match self.agent {
+            Some((x, y)) => format!("{} {}\n", x, y),
+            None => String::new(),
+        }
+    }
+
+    // Índice em que o cenário `idx` pode ser gravado. Os cenários que não vieram do
+    // editor (sem inputs/items_<idx>) são lidos pelos testes e pelo lote, então
+    // não são sobrescritos: o editor grava no primeiro índice livre.
+    pub fn save_index(idx: usize) -> usize {
+        let exists = |idx: usize| {
+            FilePath::new(&format!("fields/field_{}.txt", idx)).exists()
+                || ["tools", "factories", "agent"]
+                    .iter()
+                    .any(|name| FilePath::new(&format!("inputs/{}_{}", name, idx)).exists())
+        };
+        let edited = FilePath::new(&format!("inputs/items_{}", idx)).exists();
+        if !exists(idx) || edited {
+            return idx;
+        }
+        (1..).find(|idx| !exists(*idx)).unwrap()
+    }
+
+    // Grava o terreno, as posições e as quantidades no formato lido por
+    // `setup_board`, `spawn_tools`, `spawn_factories`, `setup_agent` e
+    // `setup_editor`. O terreno vai para um arquivo do cenário, para não mexer no
+    // mapa dos outros.
+    pub fn save(&self, idx: usize, terrain: &[Vec<Terrain>]) {
+        fs::write(format!("fields/field_{}.txt", idx), field_text(terrain))
+            .expect("Something went wrong");
+        fs::write(format!("inputs/tools_{}", idx), self.tools_text())
+            .expect("Something went wrong");
+        fs::write(
+            format!("inputs/items_{}", idx),
+            quantities_text(&self.items_quantity()),
+        )
+        .expect("Something went wrong");
+        fs::write(
+            format!("inputs/needs_{}", idx),
+            quantities_text(&self.factories_needs()),
+        )
+        .expect("Something went wrong");
+        fs::write(format!("inputs/factories_{}", idx), self.factories_text())
+            .expect("Something went wrong");
+        fs::write(format!("inputs/agent_{}", idx), self.agent_text())
+            .expect("Something went wrong");
+        println!("Scenario {} saved", idx);
+    }
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Marca onde o agente vai começar enquanto o cenário é editado.
+#[derive(Component)]
+pub struct EditorAgent;
+
+#[derive(Component)]
+pub struct EditorText;
+
+// Critério de execução dos sistemas da simulação: nada anda enquanto o editor
+// está aberto.
+pub fn simulation_tick(
+    editor: Res<Editor>,
+    time: Res<Time>,
+    mut clock: ResMut<SimulationClock>,
+    query: Query<&Motion>,
+) -> ShouldRun {
+    if editor.active || !motion_finished(&query) {
+        return ShouldRun::No;
+    }
+    clock.tick(time.delta_seconds())
+}
+
+fn spawn_factory(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    layout: &GridLayout,
+    factory: Factory,
+) {
+    let center = layout.center(GridCoord::new(factory.x, factory.y));
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: asset_server.load(factory_texture(factory.needed_tool)),
+            transform: Transform::from_translation(center.extend(2.0)),
+            sprite: Sprite {
+                custom_size: Some(layout.cell_size()),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(factory);
+}
+
+fn spawn_agent_marker(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    layout: &GridLayout,
+    (x, y): (usize, usize),
+) {
+    let center = layout.center(GridCoord::new(x, y));
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: asset_server.load("robot.png"),
+            transform: Transform::from_translation(center.extend(2.0)),
+            sprite: Sprite {
+                custom_size: Some(layout.cell_size()),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(EditorAgent);
+}
+
+// Abre o cenário `input_idx` se ele já existe, senão começa só com o terreno.
+pub fn setup_editor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    board: Res<Board>,
+    layout: Res<GridLayout>,
+    mut query: Query<&mut Cell>,
+    params: Res<Params>,
+) {
+    let idx = params.input_idx;
+    let mut editor = Editor::new();
+    let exists = ["tools", "factories", "agent"]
+        .iter()
+        .all(|name| FilePath::new(&format!("inputs/{}_{}", name, idx)).exists());
+
+    if exists {
+        let terrain = |x: usize, y: usize| query.get(board.cells[x][y]).unwrap().terrain;
+        editor.tools = place_tools(idx, &params.items_quantity, terrain);
+        let tools = &editor.tools;
+        let has_tool = |x: usize, y: usize| tools.iter().any(|t| t.x == x && t.y == y);
+        editor.factories = place_factories(idx, &params.factories_needs, |x, y| {
+            terrain(x, y).passable() && !has_tool(x, y)
+        });
+        let factories = &editor.factories;
+        editor.agent = Some(place_agent(idx, |x, y| {
+            terrain(x, y).passable()
+                && !has_tool(x, y)
+                && !factories.iter().any(|f| f.x == x && f.y == y)
+        }));
+    }
+
+    for tool in &editor.tools {
+        spawn_tool(
+            &mut commands,
+            &asset_server,
+            &layout,
+            (tool.x, tool.y),
+            tool.tool_type.unwrap(),
+        );
+        query.get_mut(board.cells[tool.x][tool.y]).unwrap().tool = tool.tool_type;
+    }
+    for factory in &editor.factories {
+        spawn_factory(&mut commands, &asset_server, &layout, *factory);
+        query
+            .get_mut(board.cells[factory.x][factory.y])
+            .unwrap()
+            .factory = Some(*factory);
+    }
+    if let Some(position) = editor.agent {
+        spawn_agent_marker(&mut commands, &asset_server, &layout, position);
+    }
+    commands.insert_resource(editor);
+}
+
+// Ponto do mundo sob o cursor, levando em conta o zoom e o deslocamento da câmera.
+fn cursor_world(
+    window: &Window,
+    transform: &Transform,
+    projection: &OrthographicProjection,
+) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    let size = Vec2::new(window.width(), window.height());
+    Some(transform.translation.truncate() + (cursor - size / 2.0) * projection.scale)
+}
+
+// 1-5 escolhem o terreno, T ferramenta, F fábrica, G agente e X apaga. Tab troca o
+// tipo de ferramenta, setas para cima e para baixo mudam a quantidade pedida
+// pelas fábricas, o botão esquerdo pinta e Enter grava o cenário e roda o agente.
+#[allow(clippy::too_many_arguments)]
+pub fn edit_board(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    board: Res<Board>,
+    layout: Res<GridLayout>,
+    mut editor: ResMut<Editor>,
+    mut params: ResMut<Params>,
+    mut query_cell: Query<&mut Cell>,
+    query_camera: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    query_tool: Query<(Entity, &Tool)>,
+    query_factory: Query<(Entity, &Factory)>,
+    query_marker: Query<Entity, With<EditorAgent>>,
+) {
+    if !editor.active {
+        return;
+    }
+
+    let brushes = [
+        (KeyCode::Key1, Brush::Terrain(Terrain::Grass)),
+        (KeyCode::Key2, Brush::Terrain(Terrain::Mountain)),
+        (KeyCode::Key3, Brush::Terrain(Terrain::Swamp)),
+        (KeyCode::Key4, Brush::Terrain(Terrain::Desert)),
+        (KeyCode::Key5, Brush::Terrain(Terrain::Obstacle)),
+        (KeyCode::T, Brush::Tool),
+        (KeyCode::F, Brush::Factory),
+        (KeyCode::G, Brush::Agent),
+        (KeyCode::X, Brush::Erase),
+    ];
+    for (key, brush) in brushes {
+        if keys.just_pressed(key) {
+            editor.brush = brush;
+        }
+    }
+    if keys.just_pressed(KeyCode::Tab) {
+        let current = TOOL_TYPES
+            .iter()
+            .position(|t| *t == editor.tool_type)
+            .unwrap();
+        editor.tool_type = TOOL_TYPES[(current + 1) % TOOL_TYPES.len()];
+    }
+    if keys.just_pressed(KeyCode::Up) {
+        editor.quantity += 1;
+    }
+    if keys.just_pressed(KeyCode::Down) && editor.quantity > 1 {
+        editor.quantity -= 1;
+    }
+
+    if keys.just_pressed(KeyCode::Return) {
+        let agent = match editor.agent {
+            Some(agent) => agent,
+            None => {
+                println!("Place the agent (G) before running");
+                return;
+            }
+        };
+        let mut terrain = vec![vec![Terrain::Grass; board.height]; board.width];
+        for (x, column) in terrain.iter_mut().enumerate() {
+            for (y, value) in column.iter_mut().enumerate() {
+                *value = query_cell.get(board.cells[x][y]).unwrap().terrain;
+            }
+        }
+        let idx = Editor::save_index(params.input_idx);
+        if idx != params.input_idx {
+            println!(
+                "Scenario {} was not made in the editor, saving as {}",
+                params.input_idx, idx
+            );
+            params.input_idx = idx;
+        }
+        editor.save(idx, &terrain);
+        params.items_quantity = editor.items_quantity();
+        params.factories_needs = editor.factories_needs();
+        for entity in query_marker.iter() {
+            commands.entity(entity).despawn();
+        }
+        spawn_agent(
+            &mut commands,
+            &asset_server,
+            &board,
+            &layout,
+            &params,
+            agent,
+            editor.factories.clone(),
+        );
+        editor.active = false;
+        return;
+    }
+
+    if !buttons.pressed(MouseButton::Left) {
+        return;
+    }
+    let (transform, projection) = match query_camera.get_single() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+    let coord = match cursor_world(windows.primary(), transform, projection)
+        .and_then(|point| layout.to_grid(point))
+    {
+        Some(coord) => coord,
+        None => return,
+    };
+    let (x, y) = (coord.x, coord.y);
+    let mut cell = query_cell.get_mut(board.entity(coord)).unwrap();
+    let brush = editor.brush;
+    if !editor.can_paint(brush, x, y, cell.terrain) {
+        return;
+    }
+
+    match brush {
+        Brush::Terrain(terrain) => {
+            if cell.terrain != terrain {
+                cell.terrain = terrain;
+            }
+        }
+        Brush::Tool => {
+            let tool_type = editor.tool_type;
+            editor.tools.push(Tool::new(x, y, Some(tool_type)));
+            cell.tool = Some(tool_type);
+            spawn_tool(&mut commands, &asset_server, &layout, (x, y), tool_type);
+        }
+        Brush::Factory => {
+            let factory = Factory::new(x, y, Some(editor.tool_type), editor.quantity);
+            editor.factories.push(factory);
+            cell.factory = Some(factory);
+            spawn_factory(&mut commands, &asset_server, &layout, factory);
+        }
+        Brush::Agent => {
+            for entity in query_marker.iter() {
+                commands.entity(entity).despawn();
+            }
+            editor.agent = Some((x, y));
+            spawn_agent_marker(&mut commands, &asset_server, &layout, (x, y));
+        }
+        Brush::Erase => {
+            editor.tools.retain(|t| t.x != x || t.y != y);
+            editor.factories.retain(|f| f.x != x || f.y != y);
+            cell.tool = None;
+            cell.factory = None;
+            for (entity, tool) in query_tool.iter() {
+                if tool.x == x && tool.y == y {
+                    commands.entity(entity).despawn();
+                }
+            }
+            for (entity, factory) in query_factory.iter() {
+                if factory.x == x && factory.y == y {
+                    commands.entity(entity).despawn();
+                }
+            }
+            if editor.agent == Some((x, y)) {
+                editor.agent = None;
+                for entity in query_marker.iter() {
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+pub fn setup_editor_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(8.0),
+                    left: Val::Px(8.0),
+                    ..default()
+                },
+                padding: Rect::all(Val::Px(6.0)),
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                            font_size: 14.0,
+                            color: Color::WHITE,
+                        },
+                        TextAlignment::default(),
+                    ),
+                    ..default()
+                })
+                .insert(EditorText);
+        });
+}
+
+pub fn update_editor_text(editor: Res<Editor>, mut query_text: Query<&mut Text, With<EditorText>>) {
+    let mut text = match query_text.get_single_mut() {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    if !editor.active {
+        text.sections[0].value = String::new();
+        return;
+    }
+    let brush = match editor.brush {
+        Brush::Terrain(terrain) => format!("{:?}", terrain),
+        Brush::Tool => format!("tool {:?}", editor.tool_type),
+        Brush::Factory => format!("factory {:?} x{}", editor.tool_type, editor.quantity),
+        Brush::Agent => "agent".to_string(),
+        Brush::Erase => "erase".to_string(),
+    };
+    let mut value = format!(
+        "Editor  brush {}\nTools {}  factories {}  agent {}",
+        brush,
+        editor.tools.len(),
+        editor.factories.len(),
+        if editor.agent.is_some() {
+            "set"
+        } else {
+            "missing"
+        }
+    );
+    value += "\n1-5 terrain  T tool  F factory  G agent  X erase";
+    value += "\nTab tool type  Up/Down quantity  Enter save and run";
+    text.sections[0].value = value;
+}
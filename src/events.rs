@@ -0,0 +1,205 @@
+use crate::agent::Agent;
+use crate::tool::ToolType;
+use crate::trajectory::{TrajectoryEvent, TrajectoryLog};
+use bevy::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AgentMoved {
+    pub x: usize,
+    pub y: usize,
+    pub cost: usize,
+    pub random: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ToolPicked {
+    pub x: usize,
+    pub y: usize,
+    pub tool: ToolType,
+}
+
+// `quantity` ferramentas deixadas na fábrica em (x, y).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ToolDelivered {
+    pub x: usize,
+    pub y: usize,
+    pub tool: ToolType,
+    pub quantity: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FactoryCompleted {
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PlanComputed {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    pub cost: Option<usize>,
+}
+
+// Custos sem a escala de `Movement`, como em `Agent::cost`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RunFinished {
+    pub cost: usize,
+    pub expansions: usize,
+    pub random_moves: usize,
+    pub random_moves_cost: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SimEvent {
+    AgentMoved(AgentMoved),
+    ToolPicked(ToolPicked),
+    ToolDelivered(ToolDelivered),
+    FactoryCompleted(FactoryCompleted),
+    PlanComputed(PlanComputed),
+    RunFinished(RunFinished),
+}
+
+impl SimEvent {
+    // Mudanças do mundo (terreno, ferramentas novas) não viram eventos do agente.
+    pub fn from_trajectory(event: TrajectoryEvent) -> Option<Self> {
+        match event {
+            TrajectoryEvent::Plan { from, to, cost } => {
+                Some(SimEvent::PlanComputed(PlanComputed { from, to, cost }))
+            }
+            TrajectoryEvent::Move { x, y, cost, random } => {
+                Some(SimEvent::AgentMoved(AgentMoved { x, y, cost, random }))
+            }
+            TrajectoryEvent::Pickup { x, y, tool } => {
+                Some(SimEvent::ToolPicked(ToolPicked { x, y, tool }))
+            }
+            TrajectoryEvent::Drop {
+                x,
+                y,
+                tool,
+                quantity,
+            } => Some(SimEvent::ToolDelivered(ToolDelivered {
+                x,
+                y,
+                tool,
+                quantity,
+            })),
+            TrajectoryEvent::Deliver { x, y } => {
+                Some(SimEvent::FactoryCompleted(FactoryCompleted { x, y }))
+            }
+            TrajectoryEvent::Terrain { .. } | TrajectoryEvent::Tool { .. } => None,
+        }
+    }
+}
+
+type Callback = Box<dyn FnMut(&SimEvent) + Send + Sync>;
+
+// Funções chamadas a cada evento, para quem não quer depender do Bevy: painéis,
+// testes ou o próprio registro lido de um arquivo com `replay_events`.
+#[derive(Default)]
+pub struct Observers {
+    callbacks: Vec<Callback>,
+}
+
+impl Observers {
+    pub fn subscribe<F>(&mut self, callback: F)
+    where
+        F: FnMut(&SimEvent) + Send + Sync + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    pub fn notify(&mut self, event: &SimEvent) {
+        for callback in &mut self.callbacks {
+            callback(event);
+        }
+    }
+}
+
+// Passa um registro inteiro pelos observadores, sem janela nem ECS, e termina com
+// `RunFinished` como `replay_agent`. O registro não guarda as expansões, que
+// ficam zeradas.
+pub fn replay_events(log: &mut TrajectoryLog, observers: &mut Observers) {
+    let mut finished = RunFinished {
+        cost: 0,
+        expansions: 0,
+        random_moves: 0,
+        random_moves_cost: 0,
+    };
+    while !log.finished() {
+        for event in log.next_tick() {
+            if let TrajectoryEvent::Move { cost, random, .. } = event {
+                if random {
+                    finished.random_moves += 1;
+                    finished.random_moves_cost += cost;
+                } else {
+                    finished.cost += cost;
+                }
+            }
+            if let Some(event) = SimEvent::from_trajectory(event) {
+                observers.notify(&event);
+            }
+        }
+        log.take_pending();
+    }
+    observers.notify(&SimEvent::RunFinished(finished));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn publish_events(
+    mut log: ResMut<TrajectoryLog>,
+    mut observers: ResMut<Observers>,
+    query: Query<&Agent>,
+    mut finished: Local<bool>,
+    mut moved: EventWriter<AgentMoved>,
+    mut picked: EventWriter<ToolPicked>,
+    mut delivered: EventWriter<ToolDelivered>,
+    mut completed: EventWriter<FactoryCompleted>,
+    mut planned: EventWriter<PlanComputed>,
+    mut run_finished: EventWriter<RunFinished>,
+) {
+    let mut events: Vec<SimEvent> = log
+        .take_pending()
+        .into_iter()
+        .filter_map(SimEvent::from_trajectory)
+        .collect();
+    if let Ok(agent) = query.get_single() {
+        if agent.ended && !*finished {
+            *finished = true;
+            events.push(SimEvent::RunFinished(RunFinished {
+                cost: agent.cost(),
+                expansions: agent.stats().expansions,
+                random_moves: agent.random_moves(),
+                random_moves_cost: agent.random_moves_cost(),
+            }));
+        }
+    }
+
+    for event in events {
+        observers.notify(&event);
+        match event {
+            SimEvent::AgentMoved(event) => moved.send(event),
+            SimEvent::ToolPicked(event) => picked.send(event),
+            SimEvent::ToolDelivered(event) => delivered.send(event),
+            SimEvent::FactoryCompleted(event) => completed.send(event),
+            SimEvent::PlanComputed(event) => planned.send(event),
+            SimEvent::RunFinished(event) => run_finished.send(event),
+        }
+    }
+}
+
+// Registra os eventos e publica o que o agente fez no fim de cada quadro, depois
+// de todos os sistemas da simulação.
+pub struct SimEventsPlugin;
+
+impl Plugin for SimEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AgentMoved>()
+            .add_event::<ToolPicked>()
+            .add_event::<ToolDelivered>()
+            .add_event::<FactoryCompleted>()
+            .add_event::<PlanComputed>()
+            .add_event::<RunFinished>()
+            .init_resource::<Observers>()
+            .add_system_to_stage(CoreStage::PostUpdate, publish_events);
+    }
+}
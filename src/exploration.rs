@@ -0,0 +1,89 @@
+use crate::belief::Belief;
+use crate::movement::Movement;
+use crate::terrain::Terrain;
+use priority_queue::PriorityQueue;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ExplorationPolicy {
+    #[default]
+    RandomWalk,
+    Frontier,
+}
+
+fn is_frontier(belief: &Belief, x: i32, y: i32, movement: &Movement) -> bool {
+    let width = belief.width as i32;
+    let height = belief.height as i32;
+    for &(mx, my) in movement.offsets() {
+        let (nx, ny) = (x + mx, y + my);
+        if nx >= 0
+            && nx < width
+            && ny >= 0
+            && ny < height
+            && !belief.known(nx as usize, ny as usize)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+// Dijkstra sobre as células já conhecidas, retornando o primeiro passo em direção
+// à fronteira (célula conhecida vizinha de uma desconhecida) mais barata. Fronteiras
+// dentro do raio do sensor têm preferência sobre as mais distantes.
+pub fn frontier_step(
+    belief: &Belief,
+    ax: i32,
+    ay: i32,
+    r: i32,
+    movement: &Movement,
+) -> Option<(i32, i32)> {
+    let width = belief.width as i32;
+    let height = belief.height as i32;
+    let mut pq = PriorityQueue::new();
+    let mut distance: HashMap<(i32, i32), i32> = HashMap::new();
+    let mut first_move: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut nearest: Option<(i32, i32)> = None;
+    let mut nearest_in_radius: Option<(i32, i32)> = None;
+
+    distance.insert((ax, ay), 0);
+    pq.push((ax, ay), 0);
+    while let Some(((cx, cy), cost)) = pq.pop() {
+        if -cost > distance[&(cx, cy)] {
+            continue;
+        }
+        if (cx, cy) != (ax, ay) && is_frontier(belief, cx, cy, movement) {
+            if nearest.is_none() {
+                nearest = Some((cx, cy));
+            }
+            if (cx - ax).abs() <= r && (cy - ay).abs() <= r {
+                nearest_in_radius = Some((cx, cy));
+                break;
+            }
+        }
+        for &(mx, my) in movement.offsets() {
+            let known =
+                |x: i32, y: i32| belief.cells[x as usize][y as usize].unwrap_or(Terrain::Obstacle);
+            if !movement.can_move((cx, cy), (mx, my), width, height, known) {
+                continue;
+            }
+            let (nx, ny) = (cx + mx, cy + my);
+            let n_cost = -cost + movement.step_cost((mx, my), known(nx, ny)) as i32;
+            if distance.contains_key(&(nx, ny)) && n_cost >= distance[&(nx, ny)] {
+                continue;
+            }
+            distance.insert((nx, ny), n_cost);
+            let step = if (cx, cy) == (ax, ay) {
+                (mx, my)
+            } else {
+                first_move[&(cx, cy)]
+            };
+            first_move.insert((nx, ny), step);
+            pq.push((nx, ny), -n_cost);
+        }
+    }
+
+    nearest_in_radius
+        .or(nearest)
+        .map(|target| first_move[&target])
+}
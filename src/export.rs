@@ -0,0 +1,208 @@
+use crate::agent::place_agent;
+use crate::board::{read_field, terrain_color, terrain_grid, Board};
+use crate::factory::*;
+use crate::grid::GridCoord;
+use crate::terrain::Terrain;
+use crate::tool::*;
+use crate::trajectory::{TrajectoryEvent, TrajectoryLog};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::FilterType;
+use image::{imageops, Delay, Frame, Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+
+// Fundo que aparece entre as células, o mesmo ClearColor padrão do Bevy.
+const BACKGROUND: Rgba<u8> = Rgba([102, 102, 102, 255]);
+
+// O que está no tabuleiro em um tick, montado com as mesmas regras de
+// `spawn_tools`, `spawn_factories` e `setup_agent`, mas sem entidades do Bevy.
+pub struct Snapshot {
+    pub terrain: Vec<Vec<Terrain>>,
+    pub tools: Vec<Tool>,
+    pub factories: Vec<Factory>,
+    pub agent: (usize, usize),
+}
+
+impl Snapshot {
+    pub fn new(
+        board: &Board,
+        input_idx: usize,
+        items_quantity: &[(ToolType, usize)],
+        factories_needs: &[(ToolType, usize)],
+    ) -> Self {
+        let terrain = terrain_grid(&read_field(input_idx), board.width, board.height);
+        let tools = place_tools(input_idx, items_quantity, |x, y| terrain[x][y]);
+        let has_tool = |x: usize, y: usize| tools.iter().any(|t| t.x == x && t.y == y);
+        let factories = place_factories(input_idx, factories_needs, |x, y| {
+            terrain[x][y].passable() && !has_tool(x, y)
+        });
+        let agent = place_agent(input_idx, |x, y| {
+            terrain[x][y].passable()
+                && !has_tool(x, y)
+                && !factories.iter().any(|f| f.x == x && f.y == y)
+        });
+        Self {
+            terrain,
+            tools,
+            factories,
+            agent,
+        }
+    }
+
+    pub fn apply(&mut self, event: TrajectoryEvent) {
+        match event {
+            TrajectoryEvent::Move { x, y, .. } => self.agent = (x, y),
+            TrajectoryEvent::Pickup { x, y, .. } => {
+                for tool in &mut self.tools {
+                    if tool.x == x && tool.y == y {
+                        tool.tool_type = None;
+                    }
+                }
+            }
+            TrajectoryEvent::Deliver { x, y } => {
+                for factory in &mut self.factories {
+                    if factory.x == x && factory.y == y {
+                        factory.needed_tool = None;
+                        factory.quantity = 0;
+                    }
+                }
+            }
+            TrajectoryEvent::Terrain { x, y, terrain } => self.terrain[x][y] = terrain,
+            TrajectoryEvent::Tool { x, y, tool } => self.tools.push(Tool::new(x, y, Some(tool))),
+            TrajectoryEvent::Plan { .. } | TrajectoryEvent::Drop { .. } => {}
+        }
+    }
+}
+
+// Desenha um `Snapshot` em memória, com a mesma disposição da janela: x cresce
+// para a direita, y para cima e as células são separadas por `border` pixels.
+pub struct Renderer {
+    pub cell_size: u32,
+    pub border: u32,
+    sprites: HashMap<&'static str, RgbaImage>,
+}
+
+impl Renderer {
+    pub fn new(cell_size: u32, border: u32) -> Self {
+        Self {
+            cell_size,
+            border,
+            sprites: HashMap::new(),
+        }
+    }
+
+    fn sprite(&mut self, name: &'static str) -> &RgbaImage {
+        let size = self.cell_size;
+        self.sprites.entry(name).or_insert_with(|| {
+            let image = image::open("assets/".to_string() + name)
+                .expect("Something went wrong")
+                .to_rgba8();
+            imageops::resize(&image, size, size, FilterType::Triangle)
+        })
+    }
+
+    fn origin(&self, size_y: usize, x: usize, y: usize) -> (u32, u32) {
+        let step = self.cell_size + self.border;
+        let (row, col) = GridCoord::new(x, y).field_index(size_y);
+        (col as u32 * step, row as u32 * step)
+    }
+
+    pub fn draw(&mut self, snapshot: &Snapshot) -> RgbaImage {
+        let size_x = snapshot.terrain.len();
+        let size_y = snapshot.terrain[0].len();
+        let step = self.cell_size + self.border;
+        let width = size_x as u32 * step - self.border;
+        let height = size_y as u32 * step - self.border;
+        let mut frame = RgbaImage::from_pixel(width, height, BACKGROUND);
+
+        for (x, row) in snapshot.terrain.iter().enumerate() {
+            for (y, terrain) in row.iter().enumerate() {
+                let (red, green, blue) = terrain_color(*terrain);
+                let color = Rgba([
+                    (red * 255.).round() as u8,
+                    (green * 255.).round() as u8,
+                    (blue * 255.).round() as u8,
+                    255,
+                ]);
+                let (left, top) = self.origin(size_y, x, y);
+                for px in left..left + self.cell_size {
+                    for py in top..top + self.cell_size {
+                        frame.put_pixel(px, py, color);
+                    }
+                }
+            }
+        }
+
+        let mut sprites: Vec<(usize, usize, &'static str)> = vec![];
+        for tool in &snapshot.tools {
+            if tool.tool_type.is_some() {
+                sprites.push((tool.x, tool.y, tool_texture(tool.tool_type)));
+            }
+        }
+        for factory in &snapshot.factories {
+            sprites.push((factory.x, factory.y, factory_texture(factory.needed_tool)));
+        }
+        sprites.push((snapshot.agent.0, snapshot.agent.1, "robot.png"));
+
+        for (x, y, name) in sprites {
+            let (left, top) = self.origin(size_y, x, y);
+            imageops::overlay(&mut frame, self.sprite(name), left, top);
+        }
+        frame
+    }
+}
+
+// Reproduz o registro sobre o `Snapshot` e grava um quadro a cada `every` ticks
+// (e sempre o último). Se `output` termina em .gif sai um GIF animado com
+// `delay_ms` por quadro, senão `output` é uma pasta com frame_00000.png, ...
+pub fn export(
+    log: &mut TrajectoryLog,
+    snapshot: &mut Snapshot,
+    renderer: &mut Renderer,
+    output: &str,
+    every: usize,
+    delay_ms: u32,
+) -> usize {
+    let mut gif = if output.ends_with(".gif") {
+        let file = File::create(output).expect("Something went wrong");
+        let mut encoder = GifEncoder::new(file);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .expect("Something went wrong");
+        Some(encoder)
+    } else {
+        fs::create_dir_all(output).expect("Something went wrong");
+        None
+    };
+
+    let mut frames = 0;
+    let mut tick = 0;
+    loop {
+        let finished = log.finished();
+        if tick % every.max(1) == 0 || finished {
+            let frame = renderer.draw(snapshot);
+            match &mut gif {
+                Some(encoder) => {
+                    let delay = Delay::from_numer_denom_ms(delay_ms, 1);
+                    encoder
+                        .encode_frame(Frame::from_parts(frame, 0, 0, delay))
+                        .expect("Something went wrong");
+                }
+                None => {
+                    let path = format!("{}/frame_{:05}.png", output, frames);
+                    frame.save(path).expect("Something went wrong");
+                }
+            }
+            frames += 1;
+        }
+        if finished {
+            break;
+        }
+        for event in log.next_tick() {
+            snapshot.apply(event);
+        }
+        tick += 1;
+    }
+    frames
+}
@@ -1,7 +1,7 @@
 use crate::board::Board;
 use crate::cell::Cell;
+use crate::grid::{GridCoord, GridLayout};
 use crate::params::Params;
-use crate::terrain::Terrain;
 use crate::tool::*;
 use bevy::prelude::*;
 use std::fs;
@@ -41,68 +41,81 @@ fn read_factories(idx: usize) -> Vec<(usize, usize)> {
     data
 }
 
+// Uma fábrica por requisição, na próxima posição de inputs/factories_<idx> que
+// `free` aceita e que não recebeu outra fábrica.
+pub fn place_factories<F>(
+    idx: usize,
+    factories_needs: &[(ToolType, usize)],
+    free: F,
+) -> Vec<Factory>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    let mut factories: Vec<Factory> = vec![];
+    let mut idx_position: usize = 0;
+    let factories_positions = read_factories(idx);
+
+    for (needed, (needed_tool, quantity)) in factories_needs.iter().enumerate() {
+        while idx_position < factories_positions.len() {
+            let (x, y) = factories_positions[idx_position];
+            idx_position += 1;
+            if free(x, y) && !factories.iter().any(|f| f.x == x && f.y == y) {
+                factories.push(Factory::new(x, y, Some(*needed_tool), *quantity));
+                break;
+            }
+        }
+        if factories.len() == needed {
+            println!(
+                "Scenario {}: no position left for the factory that needs {}",
+                idx,
+                tool_name(*needed_tool)
+            );
+        }
+    }
+    factories
+}
+
 pub fn spawn_factories(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     board: Res<Board>,
-    windows: Res<Windows>,
+    layout: Res<GridLayout>,
     mut query: Query<&mut Cell>,
     params: Res<Params>,
 ) {
     asset_server.watch_for_changes().unwrap();
 
-    let window = windows.primary();
-    let border_width = 2.0;
-    let cell_width =
-        (window.width() - border_width * (board.width - 1) as f32) / (board.width as f32);
-    let cell_height =
-        (window.height() - border_width * (board.height - 1) as f32) / (board.height as f32);
-
-    let mut idx: usize = 0;
-    let factories_positions = read_factories(params.input_idx);
-
-    for (needed_tool, quantity) in &params.factories_needs {
-        let mut cont = 0;
-        while cont < 1 {
-            let (x, y) = factories_positions[idx];
-            idx += 1;
-            let mut cell = query.get_mut(board.cells[x][y]).unwrap();
-            match cell.terrain {
-                Terrain::Obstacle => {}
-                _ => match cell.tool {
-                    Some(_) => {}
-                    None => match cell.factory {
-                        Some(_) => {}
-                        None => {
-                            let xx = x as f32;
-                            let yy = y as f32;
-                            let cx = -window.height() / 2.
-                                + cell_height * xx
-                                + border_width * xx
-                                + cell_height / 2.;
-                            let cy = -window.width() / 2.
-                                + cell_width * yy
-                                + border_width * yy
-                                + cell_width / 2.;
-                            let factory = Factory::new(x, y, Some(*needed_tool), *quantity);
-                            commands
-                                .spawn_bundle(SpriteBundle {
-                                    texture: asset_server.load("factory.png"),
-                                    transform: Transform::from_xyz(cx, cy, 2.0),
-                                    sprite: Sprite {
-                                        custom_size: Some(Vec2::new(cell_width, cell_height)),
-                                        ..default()
-                                    },
-                                    ..default()
-                                })
-                                .insert(factory);
-                            cell.factory = Some(factory);
-                            cont += 1;
-                        }
-                    },
+    let factories = place_factories(params.input_idx, &params.factories_needs, |x, y| {
+        let cell = query.get(board.cells[x][y]).unwrap();
+        cell.terrain.passable() && cell.tool.is_none() && cell.factory.is_none()
+    });
+    for factory in factories {
+        let (x, y) = (factory.x, factory.y);
+        let center = layout.center(GridCoord::new(x, y));
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: asset_server.load("factory.png"),
+                transform: Transform::from_translation(center.extend(2.0)),
+                sprite: Sprite {
+                    custom_size: Some(layout.cell_size()),
+                    ..default()
                 },
-            }
-        }
+                ..default()
+            })
+            .insert(factory);
+        let mut cell = query.get_mut(board.cells[x][y]).unwrap();
+        cell.factory = Some(factory);
+    }
+}
+
+pub fn factory_texture(needed_tool: Option<ToolType>) -> &'static str {
+    match needed_tool {
+        Some(ToolType::Battery) => "battery_factory.png",
+        Some(ToolType::WeldingArm) => "welding_factory.png",
+        Some(ToolType::SuctionPump) => "pump_factory.png",
+        Some(ToolType::CoolingDevice) => "cooling_factory.png",
+        Some(ToolType::PneumaticArm) => "pneumatic_factory.png",
+        None => "factory.png",
     }
 }
 
@@ -112,17 +125,6 @@ pub fn render_factories(
 ) {
     for (factory, mut image_handle) in query.iter_mut() {
         // println!("Factory: {:?}", factory);
-        match factory.needed_tool {
-            Some(ToolType::Battery) => *image_handle = asset_server.load("battery_factory.png"),
-            Some(ToolType::WeldingArm) => *image_handle = asset_server.load("welding_factory.png"),
-            Some(ToolType::SuctionPump) => *image_handle = asset_server.load("pump_factory.png"),
-            Some(ToolType::CoolingDevice) => {
-                *image_handle = asset_server.load("cooling_factory.png")
-            }
-            Some(ToolType::PneumaticArm) => {
-                *image_handle = asset_server.load("pneumatic_factory.png")
-            }
-            None => *image_handle = asset_server.load("factory.png"),
-        }
+        *image_handle = asset_server.load(factory_texture(factory.needed_tool));
     }
 }
@@ -0,0 +1,92 @@
+use crate::board::Board;
+use bevy::prelude::*;
+
+// Posição de uma célula. x é a coluna, cresce para a direita e vai até
+// `board.width`; y é a linha contada de baixo para cima e vai até `board.height`.
+// É a mesma convenção de `Board::cells[x][y]` e dos planejadores, e toda conversão
+// para a tela ou para o arquivo do mapa passa por aqui.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct GridCoord {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl GridCoord {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    // Coordenadas com sinal, como os planejadores usam. None fora do tabuleiro.
+    pub fn from_signed((x, y): (i32, i32), width: usize, height: usize) -> Option<Self> {
+        if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+            return None;
+        }
+        Some(Self::new(x as usize, y as usize))
+    }
+
+    pub fn signed(&self) -> (i32, i32) {
+        (self.x as i32, self.y as i32)
+    }
+
+    // (linha, coluna) em fields/field.txt, onde a primeira linha é o topo da tela.
+    pub fn field_index(&self, height: usize) -> (usize, usize) {
+        (height - self.y - 1, self.x)
+    }
+}
+
+// Tamanho das células e onde fica o centro de cada uma no mundo. É montado em
+// `setup_board` a partir da janela e refeito por `resize_layout` quando ela muda
+// de tamanho; todo sistema que posiciona sprites passa por aqui.
+#[derive(Clone, Copy, Debug)]
+pub struct GridLayout {
+    pub width: f32,
+    pub height: f32,
+    pub border_width: f32,
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl GridLayout {
+    pub fn new(width: f32, height: f32, board: &Board) -> Self {
+        let border_width = 2.0;
+        Self {
+            width,
+            height,
+            border_width,
+            cell_width: (width - border_width * (board.width - 1) as f32) / (board.width as f32),
+            cell_height: (height - border_width * (board.height - 1) as f32)
+                / (board.height as f32),
+            columns: board.width,
+            rows: board.height,
+        }
+    }
+
+    pub fn from_window(window: &Window, board: &Board) -> Self {
+        Self::new(window.width(), window.height(), board)
+    }
+
+    pub fn cell_size(&self) -> Vec2 {
+        Vec2::new(self.cell_width, self.cell_height)
+    }
+
+    pub fn center(&self, coord: GridCoord) -> Vec2 {
+        let (x, y) = (coord.x as f32, coord.y as f32);
+        Vec2::new(
+            -self.width / 2. + (self.cell_width + self.border_width) * x + self.cell_width / 2.,
+            -self.height / 2. + (self.cell_height + self.border_width) * y + self.cell_height / 2.,
+        )
+    }
+
+    // Célula sob um ponto do mundo; a borda entre duas células conta para a da
+    // esquerda/de baixo.
+    pub fn to_grid(&self, point: Vec2) -> Option<GridCoord> {
+        let x = (point.x + self.width / 2.) / (self.cell_width + self.border_width);
+        let y = (point.y + self.height / 2.) / (self.cell_height + self.border_width);
+        if x < 0. || y < 0. {
+            return None;
+        }
+        GridCoord::from_signed((x as i32, y as i32), self.columns, self.rows)
+    }
+}
@@ -0,0 +1,451 @@
+use crate::movement::Movement;
+use crate::search::{print_distance, SearchStats};
+use crate::terrain::Terrain;
+use priority_queue::PriorityQueue;
+use std::collections::{BTreeMap, HashMap};
+
+type Cluster = (i32, i32);
+type Border = (Cluster, Cluster);
+type Transition = ((i32, i32), (i32, i32));
+type Edge = ((i32, i32), (i32, i32), i32);
+type Graph = HashMap<(i32, i32), Vec<((i32, i32), i32)>>;
+type Connections = Vec<((i32, i32), i32)>;
+
+// HPA* (Botea, Müller e Schaeffer): o mapa é dividido em clusters quadrados, as
+// entradas entre clusters vizinhos viram nós de um grafo abstrato e os custos
+// dentro de cada cluster são pré-calculados. Quando o terreno muda só o cluster
+// da célula é refeito, e o vizinho só quando a célula está na borda entre os dois.
+// O grafo das entradas fica pronto entre as buscas, e as ligações de uma origem ou
+// destino com as entradas do seu cluster são guardadas até o cluster mudar.
+pub struct Hpa {
+    width: i32,
+    height: i32,
+    cluster_size: i32,
+    movement: Movement,
+    terrain: Vec<Vec<Terrain>>,
+    // Ordenados para que o grafo abstrato, e com ele os empates da busca, saia
+    // igual em toda execução.
+    transitions: BTreeMap<Border, Vec<Transition>>,
+    intra: BTreeMap<Cluster, Vec<Edge>>,
+    graph: Graph,
+    // Custo de cada célula já usada como origem (false) ou destino (true) até as
+    // entradas do seu cluster.
+    connections: HashMap<((i32, i32), bool), Connections>,
+}
+
+struct LocalSearch {
+    distance: HashMap<(i32, i32), i32>,
+    parent: HashMap<(i32, i32), (i32, i32)>,
+}
+
+impl Hpa {
+    pub fn new<F>(
+        width: i32,
+        height: i32,
+        cluster_size: i32,
+        movement: Movement,
+        terrain: F,
+    ) -> Self
+    where
+        F: Fn(i32, i32) -> Terrain,
+    {
+        let mut hpa = Self {
+            width,
+            height,
+            cluster_size,
+            movement,
+            terrain: vec![vec![Terrain::Grass; height as usize]; width as usize],
+            transitions: BTreeMap::new(),
+            intra: BTreeMap::new(),
+            graph: HashMap::new(),
+            connections: HashMap::new(),
+        };
+        for x in 0..width {
+            for y in 0..height {
+                hpa.terrain[x as usize][y as usize] = terrain(x, y);
+            }
+        }
+        let clusters = hpa.clusters();
+        for cluster in &clusters {
+            for border in hpa.borders(*cluster) {
+                if border.0 == *cluster {
+                    hpa.build_border(border);
+                }
+            }
+        }
+        for cluster in clusters {
+            hpa.build_cluster(cluster);
+        }
+        hpa.build_graph();
+        hpa
+    }
+
+    fn clusters(&self) -> Vec<Cluster> {
+        let mut clusters = vec![];
+        for cx in 0..(self.width + self.cluster_size - 1) / self.cluster_size {
+            for cy in 0..(self.height + self.cluster_size - 1) / self.cluster_size {
+                clusters.push((cx, cy));
+            }
+        }
+        clusters
+    }
+
+    fn cluster_of(&self, (x, y): (i32, i32)) -> Cluster {
+        (x / self.cluster_size, y / self.cluster_size)
+    }
+
+    fn bounds(&self, (cx, cy): Cluster) -> (i32, i32, i32, i32) {
+        let x0 = cx * self.cluster_size;
+        let y0 = cy * self.cluster_size;
+        (
+            x0,
+            y0,
+            (x0 + self.cluster_size).min(self.width),
+            (y0 + self.cluster_size).min(self.height),
+        )
+    }
+
+    fn borders(&self, (cx, cy): Cluster) -> Vec<Border> {
+        let mut borders = vec![];
+        let (x0, y0, x1, y1) = self.bounds((cx, cy));
+        if x0 > 0 {
+            borders.push(((cx - 1, cy), (cx, cy)));
+        }
+        if x1 < self.width {
+            borders.push(((cx, cy), (cx + 1, cy)));
+        }
+        if y0 > 0 {
+            borders.push(((cx, cy - 1), (cx, cy)));
+        }
+        if y1 < self.height {
+            borders.push(((cx, cy), (cx, cy + 1)));
+        }
+        borders
+    }
+
+    fn terrain_at(&self, x: i32, y: i32) -> Terrain {
+        self.terrain[x as usize][y as usize]
+    }
+
+    // Os pares de células vizinhas, uma de cada lado da borda.
+    fn border_pairs(&self, (a, b): Border) -> Vec<Transition> {
+        let (ax0, ay0, ax1, ay1) = self.bounds(a);
+        if b.0 != a.0 {
+            (ay0..ay1).map(|y| ((ax1 - 1, y), (ax1, y))).collect()
+        } else {
+            (ax0..ax1).map(|x| ((x, ay1 - 1), (x, ay1))).collect()
+        }
+    }
+
+    // Percorre a borda entre dois clusters e cria uma transição no meio de cada
+    // trecho livre em que o par de células tem o mesmo custo dos dois lados.
+    fn build_border(&mut self, border: Border) {
+        let pairs = self.border_pairs(border);
+
+        let mut transitions = vec![];
+        let mut run: Vec<Transition> = vec![];
+        for pair in pairs
+            .iter()
+            .copied()
+            .chain(std::iter::once(((-1, -1), (-1, -1))))
+        {
+            let open = pair.0 .0 >= 0
+                && self.terrain_at(pair.0 .0, pair.0 .1).passable()
+                && self.terrain_at(pair.1 .0, pair.1 .1).passable();
+            let same = match run.last() {
+                Some(last) => {
+                    open && self.terrain_at(last.0 .0, last.0 .1)
+                        == self.terrain_at(pair.0 .0, pair.0 .1)
+                        && self.terrain_at(last.1 .0, last.1 .1)
+                            == self.terrain_at(pair.1 .0, pair.1 .1)
+                }
+                None => open,
+            };
+            if !same && !run.is_empty() {
+                transitions.push(run[run.len() / 2]);
+                run.clear();
+            }
+            if open {
+                run.push(pair);
+            }
+        }
+        self.transitions.insert(border, transitions);
+    }
+
+    fn nodes(&self, cluster: Cluster) -> Vec<(i32, i32)> {
+        let mut nodes = vec![];
+        for border in self.borders(cluster) {
+            if let Some(transitions) = self.transitions.get(&border) {
+                for (a, b) in transitions {
+                    for cell in [*a, *b] {
+                        if self.cluster_of(cell) == cluster && !nodes.contains(&cell) {
+                            nodes.push(cell);
+                        }
+                    }
+                }
+            }
+        }
+        nodes
+    }
+
+    // Dijkstra limitado ao cluster. Com `reverse` os custos são os da aresta no
+    // sentido contrário, o que dá a distância de cada célula até `source`.
+    fn local_search(
+        &self,
+        cluster: Cluster,
+        source: (i32, i32),
+        reverse: bool,
+        stats: &mut SearchStats,
+    ) -> LocalSearch {
+        let (x0, y0, x1, y1) = self.bounds(cluster);
+        let mut pq = PriorityQueue::new();
+        let mut distance: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+        let terrain = |x: i32, y: i32| self.terrain_at(x, y);
+        distance.insert(source, 0);
+        pq.push(source, 0);
+        while let Some(((cx, cy), cost)) = pq.pop() {
+            if -cost > distance[&(cx, cy)] {
+                continue;
+            }
+            stats.expand(pq.len() + 1);
+            for &(mx, my) in self.movement.offsets() {
+                let (nx, ny) = (cx + mx, cy + my);
+                if nx < x0 || nx >= x1 || ny < y0 || ny >= y1 {
+                    continue;
+                }
+                if !self
+                    .movement
+                    .can_move((cx, cy), (mx, my), self.width, self.height, terrain)
+                {
+                    continue;
+                }
+                let entered = if reverse { (cx, cy) } else { (nx, ny) };
+                let step = self
+                    .movement
+                    .step_cost((mx, my), self.terrain_at(entered.0, entered.1));
+                let n_cost = -cost + step as i32;
+                if distance.contains_key(&(nx, ny)) && n_cost >= distance[&(nx, ny)] {
+                    continue;
+                }
+                distance.insert((nx, ny), n_cost);
+                parent.insert((nx, ny), (cx, cy));
+                pq.push((nx, ny), -n_cost);
+            }
+        }
+        LocalSearch { distance, parent }
+    }
+
+    fn build_cluster(&mut self, cluster: Cluster) {
+        let nodes = self.nodes(cluster);
+        let mut edges = vec![];
+        let mut stats = SearchStats::default();
+        for from in &nodes {
+            let search = self.local_search(cluster, *from, false, &mut stats);
+            for to in &nodes {
+                if from != to && search.distance.contains_key(to) {
+                    edges.push((*from, *to, search.distance[to]));
+                }
+            }
+        }
+        self.intra.insert(cluster, edges);
+    }
+
+    // O grafo abstrato: as transições nos dois sentidos e as arestas dentro de
+    // cada cluster.
+    fn build_graph(&mut self) {
+        let mut graph: Graph = HashMap::new();
+        for transitions in self.transitions.values() {
+            for (a, b) in transitions {
+                let (mx, my) = (b.0 - a.0, b.1 - a.1);
+                let to_b = self.movement.step_cost((mx, my), self.terrain_at(b.0, b.1));
+                let to_a = self
+                    .movement
+                    .step_cost((-mx, -my), self.terrain_at(a.0, a.1));
+                graph.entry(*a).or_default().push((*b, to_b as i32));
+                graph.entry(*b).or_default().push((*a, to_a as i32));
+            }
+        }
+        for edges in self.intra.values() {
+            for (from, to, cost) in edges {
+                graph.entry(*from).or_default().push((*to, *cost));
+            }
+        }
+        self.graph = graph;
+    }
+
+    // Custos de `cell` até as entradas do seu cluster, ou delas até `cell` com
+    // `reverse`. Só busca dentro do cluster na primeira vez.
+    fn connections(
+        &mut self,
+        cell: (i32, i32),
+        reverse: bool,
+        stats: &mut SearchStats,
+    ) -> Connections {
+        if let Some(connections) = self.connections.get(&(cell, reverse)) {
+            return connections.clone();
+        }
+        let cluster = self.cluster_of(cell);
+        let search = self.local_search(cluster, cell, reverse, stats);
+        let connections: Connections = self
+            .nodes(cluster)
+            .into_iter()
+            .filter_map(|node| search.distance.get(&node).map(|cost| (node, *cost)))
+            .collect();
+        self.connections
+            .insert((cell, reverse), connections.clone());
+        connections
+    }
+
+    // Compara o terreno com a cópia guardada e refaz só as bordas e clusters
+    // afetados. Retorna quantos clusters foram recalculados.
+    pub fn update<F>(&mut self, terrain: F) -> usize
+    where
+        F: Fn(i32, i32) -> Terrain,
+    {
+        let mut borders: Vec<Border> = vec![];
+        let mut rebuild: Vec<Cluster> = vec![];
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let current = terrain(x, y);
+                if self.terrain[x as usize][y as usize] == current {
+                    continue;
+                }
+                self.terrain[x as usize][y as usize] = current;
+                let cluster = self.cluster_of((x, y));
+                if !rebuild.contains(&cluster) {
+                    rebuild.push(cluster);
+                }
+                // As transições só mudam se a célula está numa borda; aí o vizinho
+                // do outro lado também ganha ou perde nós.
+                for border in self.borders(cluster) {
+                    let on_border = self
+                        .border_pairs(border)
+                        .iter()
+                        .any(|(a, b)| *a == (x, y) || *b == (x, y));
+                    if !on_border {
+                        continue;
+                    }
+                    if !borders.contains(&border) {
+                        borders.push(border);
+                    }
+                    for side in [border.0, border.1] {
+                        if !rebuild.contains(&side) {
+                            rebuild.push(side);
+                        }
+                    }
+                }
+            }
+        }
+
+        for border in &borders {
+            self.build_border(*border);
+        }
+        for cluster in &rebuild {
+            self.build_cluster(*cluster);
+        }
+        if !rebuild.is_empty() {
+            let size = self.cluster_size;
+            self.connections
+                .retain(|(cell, _), _| !rebuild.contains(&(cell.0 / size, cell.1 / size)));
+            self.build_graph();
+        }
+        rebuild.len()
+    }
+
+    fn refine(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+        moves: &mut Vec<(i32, i32)>,
+        stats: &mut SearchStats,
+    ) {
+        if self.cluster_of(from) != self.cluster_of(to) {
+            moves.push((to.0 - from.0, to.1 - from.1));
+            return;
+        }
+        let search = self.local_search(self.cluster_of(from), from, false, stats);
+        let mut segment = vec![];
+        let mut current = to;
+        while current != from {
+            let previous = search.parent[&current];
+            segment.push((current.0 - previous.0, current.1 - previous.1));
+            current = previous;
+        }
+        segment.reverse();
+        moves.extend(segment);
+    }
+
+    pub fn find_path(
+        &mut self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        stats: &mut SearchStats,
+    ) -> Option<Vec<(i32, i32)>> {
+        println!(
+            "\nHierarchical search from ({}, {}) to ({}, {})",
+            start.0, start.1, goal.0, goal.1
+        );
+        // Só a origem e o destino entram no grafo a cada busca.
+        let mut extra: Graph = HashMap::new();
+        for (node, cost) in self.connections(start, false, stats) {
+            extra.entry(start).or_default().push((node, cost));
+        }
+        for (node, cost) in self.connections(goal, true, stats) {
+            extra.entry(node).or_default().push((goal, cost));
+        }
+        let start_cluster = self.cluster_of(start);
+        if start_cluster == self.cluster_of(goal) {
+            let from_start = self.local_search(start_cluster, start, false, stats);
+            if let Some(cost) = from_start.distance.get(&goal) {
+                extra.entry(start).or_default().push((goal, *cost));
+            }
+        }
+
+        let mut pq = PriorityQueue::new();
+        let mut distance: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+        distance.insert(start, 0);
+        pq.push(start, -self.movement.heuristic(start, goal));
+        while let Some((current, priority)) = pq.pop() {
+            if current == goal {
+                break;
+            }
+            if -priority > distance[&current] + self.movement.heuristic(current, goal) {
+                continue;
+            }
+            stats.expand(pq.len() + 1);
+            let edges = self.graph.get(&current).into_iter().flatten();
+            for (next, cost) in edges.chain(extra.get(&current).into_iter().flatten()) {
+                let n_distance = distance[&current] + cost;
+                if distance.contains_key(next) && n_distance >= distance[next] {
+                    continue;
+                }
+                distance.insert(*next, n_distance);
+                parent.insert(*next, current);
+                pq.push(*next, -(n_distance + self.movement.heuristic(*next, goal)));
+            }
+        }
+
+        let terrain = |x: i32, y: i32| self.terrain_at(x, y);
+        if start == goal || !distance.contains_key(&goal) {
+            print_distance(start, goal, None, &self.movement, terrain);
+            return None;
+        }
+
+        let mut abstract_path = vec![goal];
+        while let Some(previous) = parent.get(abstract_path.last().unwrap()) {
+            abstract_path.push(*previous);
+        }
+        abstract_path.reverse();
+        println!("Abstract path: {:?}", abstract_path);
+
+        let mut moves: Vec<(i32, i32)> = vec![];
+        for pair in abstract_path.windows(2) {
+            self.refine(pair[0], pair[1], &mut moves, stats);
+        }
+        print_distance(start, goal, Some(&moves), &self.movement, terrain);
+        println!("Path: {:?}", moves);
+        Some(moves)
+    }
+}
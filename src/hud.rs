@@ -0,0 +1,150 @@
+use crate::agent::Agent;
+use crate::clock::SimulationClock;
+use crate::params::Params;
+use crate::path::Path;
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct HudText;
+
+// Fica na interface, e não no mundo, para não sair do lugar com zoom e
+// movimento da câmera.
+pub fn setup_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(8.0),
+                    left: Val::Px(8.0),
+                    ..default()
+                },
+                padding: Rect::all(Val::Px(6.0)),
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                            font_size: 14.0,
+                            color: Color::WHITE,
+                        },
+                        TextAlignment::default(),
+                    ),
+                    ..default()
+                })
+                .insert(HudText);
+        });
+}
+
+pub fn update_hud(
+    clock: Res<SimulationClock>,
+    query: Query<&Agent>,
+    mut query_text: Query<&mut Text, With<HudText>>,
+) {
+    let mut text = match query_text.get_single_mut() {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    let speed = match clock.speed {
+        Some(speed) => format!("{} ticks/s", speed),
+        None => "max".to_string(),
+    };
+    let state = if clock.paused { "paused" } else { "running" };
+    let mut value = format!("Tick {}  speed {}  {}", clock.ticks, speed, state);
+    if let (Some(expansion), Ok(agent)) = (clock.expansion, query.get_single()) {
+        value += &format!(
+            "\nExpansion {}/{}",
+            expansion,
+            agent.last_search().closed.len()
+        );
+    }
+    value += "\nSpace pause  N step  +/- speed  E expansions  M animation";
+    value += "\nWheel zoom  WASD/right drag pan  Home reset";
+    text.sections[0].value = value;
+}
+
+#[derive(Component)]
+pub struct MetricsText;
+
+// Painel no canto superior direito com o inventário, as requisições que faltam e
+// os números que antes só apareciam no fim da execução.
+pub fn setup_metrics_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(8.0),
+                    right: Val::Px(8.0),
+                    ..default()
+                },
+                padding: Rect::all(Val::Px(6.0)),
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                            font_size: 13.0,
+                            color: Color::WHITE,
+                        },
+                        TextAlignment::default(),
+                    ),
+                    ..default()
+                })
+                .insert(MetricsText);
+        });
+}
+
+pub fn update_metrics_panel(
+    follow_path: Res<Path>,
+    params: Res<Params>,
+    query: Query<&Agent>,
+    mut query_text: Query<&mut Text, With<MetricsText>>,
+) {
+    let (agent, mut text) = match (query.get_single(), query_text.get_single_mut()) {
+        (Ok(agent), Ok(text)) => (agent, text),
+        _ => return,
+    };
+    let scale = params.movement.scale() as f64;
+    let destination = match follow_path.destination {
+        Some((x, y)) => format!("({}, {})", x, y),
+        None => "-".to_string(),
+    };
+    let mut value = format!(
+        "Cost: {}\nExpansions: {}\nPeak open list: {}\nRandom moves: {} (cost {})\nDestination: {}\n",
+        agent.cost() as f64 / scale,
+        agent.stats().expansions,
+        agent.stats().peak_open,
+        agent.random_moves(),
+        agent.random_moves_cost() as f64 / scale,
+        destination
+    );
+    value += "\nInventory\n";
+    for (tool_type, quantity) in agent.inventory() {
+        value += &format!("  {:?}: {}\n", tool_type, quantity);
+    }
+    value += "\nRequisitions\n";
+    for factory in agent.requisitions() {
+        if let Some(tool_type) = factory.needed_tool {
+            value += &format!(
+                "  {} {:?} at ({}, {})\n",
+                factory.quantity, tool_type, factory.x, factory.y
+            );
+        }
+    }
+    text.sections[0].value = value;
+}
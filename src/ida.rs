@@ -0,0 +1,119 @@
+use crate::movement::Movement;
+use crate::search::{print_distance, SearchStats};
+use crate::terrain::Terrain;
+
+// IDA*: busca em profundidade limitada por f = g + h, aumentando o limite para o
+// menor f que passou do limite anterior. Só guarda o caminho atual, então a
+// memória cresce com o tamanho do caminho e não com o número de nós visitados.
+struct Ida<'a, F: Fn(i32, i32) -> Terrain> {
+    width: i32,
+    height: i32,
+    goal: (i32, i32),
+    terrain: &'a F,
+    movement: &'a Movement,
+    on_path: Vec<bool>,
+    path: Vec<(i32, i32)>,
+    max_expansions: usize,
+}
+
+enum Outcome {
+    Found(i32),
+    Next(i32),
+    Stop,
+}
+
+impl<'a, F: Fn(i32, i32) -> Terrain> Ida<'a, F> {
+    fn id(&self, (x, y): (i32, i32)) -> usize {
+        (x * self.height + y) as usize
+    }
+
+    fn search(&mut self, node: (i32, i32), g: i32, bound: i32, stats: &mut SearchStats) -> Outcome {
+        let f = g + self.movement.heuristic(node, self.goal);
+        if f > bound {
+            return Outcome::Next(f);
+        }
+        if node == self.goal {
+            return Outcome::Found(g);
+        }
+        if stats.expansions >= self.max_expansions {
+            return Outcome::Stop;
+        }
+        stats.expand(self.path.len() + 1);
+        let mut next_bound = i32::MAX;
+        for &(mx, my) in self.movement.offsets() {
+            if !self
+                .movement
+                .can_move(node, (mx, my), self.width, self.height, self.terrain)
+            {
+                continue;
+            }
+            let next = (node.0 + mx, node.1 + my);
+            let id = self.id(next);
+            if self.on_path[id] {
+                continue;
+            }
+            let step = self
+                .movement
+                .step_cost((mx, my), (self.terrain)(next.0, next.1)) as i32;
+            self.on_path[id] = true;
+            self.path.push((mx, my));
+            match self.search(next, g + step, bound, stats) {
+                Outcome::Found(cost) => return Outcome::Found(cost),
+                Outcome::Stop => return Outcome::Stop,
+                Outcome::Next(f) => next_bound = next_bound.min(f),
+            }
+            self.path.pop();
+            self.on_path[id] = false;
+        }
+        Outcome::Next(next_bound)
+    }
+}
+
+// `max_expansions` limita o total de expansões somando todas as iterações, já que
+// com muitos custos diferentes o limite cresce devagar e as iterações se repetem.
+#[allow(clippy::too_many_arguments)]
+pub fn ida_star<F>(
+    (ax, ay): (i32, i32),
+    (dx, dy): (i32, i32),
+    width: i32,
+    height: i32,
+    terrain: F,
+    movement: &Movement,
+    max_expansions: usize,
+    stats: &mut SearchStats,
+) -> Option<Vec<(i32, i32)>>
+where
+    F: Fn(i32, i32) -> Terrain,
+{
+    let mut ida = Ida {
+        width,
+        height,
+        goal: (dx, dy),
+        terrain: &terrain,
+        movement,
+        on_path: vec![false; (width * height) as usize],
+        path: vec![],
+        max_expansions: stats.expansions + max_expansions,
+    };
+    println!("\nIDA* search from ({}, {}) to ({}, {})", ax, ay, dx, dy);
+    let start = (ax, ay);
+    let start_id = ida.id(start);
+    ida.on_path[start_id] = true;
+    let mut bound = movement.heuristic(start, (dx, dy));
+    let cost = loop {
+        println!("Bound: {}", bound);
+        match ida.search(start, 0, bound, stats) {
+            Outcome::Found(cost) => break Some(cost),
+            Outcome::Next(i32::MAX) | Outcome::Stop => break None,
+            Outcome::Next(next) => bound = next,
+        }
+    };
+
+    if cost.is_none() || start == (dx, dy) {
+        print_distance(start, (dx, dy), None, movement, &terrain);
+        return None;
+    }
+    print_distance(start, (dx, dy), Some(&ida.path), movement, &terrain);
+    println!("Path: {:?}", ida.path);
+    Some(ida.path)
+}
@@ -0,0 +1,186 @@
+use crate::movement::{Connectivity, Movement};
+use crate::search::{print_distance, valid, SearchStats};
+use crate::terrain::Terrain;
+use priority_queue::PriorityQueue;
+use std::collections::HashMap;
+
+// Jump Point Search para grades com custos diferentes: a poda por simetria só é
+// aplicada dentro de regiões em que a vizinhança 3x3 inteira tem o mesmo custo.
+// Qualquer célula encostada em outro terreno ou em obstáculo vira ponto de salto
+// e é expandida como no A* comum. A borda do mapa não cria pontos de salto, já
+// que é reta e não forma cantos.
+struct Grid<'a, F: Fn(i32, i32) -> Terrain> {
+    width: i32,
+    height: i32,
+    goal: (i32, i32),
+    terrain: &'a F,
+    movement: &'a Movement,
+}
+
+impl<'a, F: Fn(i32, i32) -> Terrain> Grid<'a, F> {
+    fn uniform(&self, (x, y): (i32, i32)) -> bool {
+        let cost = (self.terrain)(x, y);
+        for mx in -1..=1 {
+            for my in -1..=1 {
+                let (nx, ny) = (x + mx, y + my);
+                if !valid(nx, ny, self.width, self.height) {
+                    continue;
+                }
+                let terrain = (self.terrain)(nx, ny);
+                if !terrain.passable() || terrain.cost() != cost.cost() {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn can_move(&self, from: (i32, i32), step: (i32, i32)) -> bool {
+        self.movement
+            .can_move(from, step, self.width, self.height, self.terrain)
+    }
+
+    fn jump(&self, from: (i32, i32), (dx, dy): (i32, i32)) -> Option<(i32, i32)> {
+        let mut current = from;
+        loop {
+            if !self.can_move(current, (dx, dy)) {
+                return None;
+            }
+            current = (current.0 + dx, current.1 + dy);
+            if current == self.goal || !self.uniform(current) {
+                return Some(current);
+            }
+            let branches: &[(i32, i32)] = match self.movement.connectivity {
+                // Com 4 vizinhos a ordem canônica é: primeiro os passos em x, depois em y.
+                Connectivity::Four if dx != 0 => &[(0, 1), (0, -1)],
+                Connectivity::Four => &[],
+                Connectivity::Eight if dx != 0 && dy != 0 => &[(1, 0), (0, 1)],
+                Connectivity::Eight => &[],
+            };
+            for &(bx, by) in branches {
+                let branch = match self.movement.connectivity {
+                    Connectivity::Four => (bx, by),
+                    Connectivity::Eight => (bx * dx, by * dy),
+                };
+                if self.jump(current, branch).is_some() {
+                    return Some(current);
+                }
+            }
+        }
+    }
+
+    fn directions(&self, node: (i32, i32), parent: Option<(i32, i32)>) -> Vec<(i32, i32)> {
+        let (dx, dy) = match parent {
+            Some(parent) if self.uniform(node) => {
+                ((node.0 - parent.0).signum(), (node.1 - parent.1).signum())
+            }
+            _ => return self.movement.offsets().to_vec(),
+        };
+        match self.movement.connectivity {
+            Connectivity::Four if dx != 0 => vec![(dx, 0), (0, 1), (0, -1)],
+            Connectivity::Four => vec![(0, dy)],
+            Connectivity::Eight if dx != 0 && dy != 0 => vec![(dx, dy), (dx, 0), (0, dy)],
+            Connectivity::Eight => vec![(dx, dy)],
+        }
+    }
+
+    fn segment_cost(&self, from: (i32, i32), to: (i32, i32)) -> i32 {
+        let step = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+        let mut cost = 0;
+        let mut current = from;
+        while current != to {
+            current = (current.0 + step.0, current.1 + step.1);
+            cost += self
+                .movement
+                .step_cost(step, (self.terrain)(current.0, current.1)) as i32;
+        }
+        cost
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn jump_point_search<F>(
+    (ax, ay): (i32, i32),
+    (dx, dy): (i32, i32),
+    width: i32,
+    height: i32,
+    terrain: F,
+    movement: &Movement,
+    g_factor: i32,
+    h_factor: i32,
+    stats: &mut SearchStats,
+) -> Option<Vec<(i32, i32)>>
+where
+    F: Fn(i32, i32) -> Terrain,
+{
+    let grid = Grid {
+        width,
+        height,
+        goal: (dx, dy),
+        terrain: &terrain,
+        movement,
+    };
+    let start = (ax, ay);
+    let goal = (dx, dy);
+    let mut pq = PriorityQueue::new();
+    let mut distance: HashMap<(i32, i32), i32> = HashMap::new();
+    let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut closed: HashMap<(i32, i32), bool> = HashMap::new();
+
+    println!(
+        "\nJump point search from ({}, {}) to ({}, {})",
+        ax, ay, dx, dy
+    );
+    distance.insert(start, 0);
+    pq.push(start, 0);
+    while let Some((current, _)) = pq.pop() {
+        if current == goal {
+            break;
+        }
+        closed.insert(current, true);
+        stats.expand(pq.len() + 1);
+        stats.close(current);
+        for direction in grid.directions(current, parent.get(&current).copied()) {
+            let next = match grid.jump(current, direction) {
+                Some(next) => next,
+                None => continue,
+            };
+            if closed.contains_key(&next) {
+                continue;
+            }
+            let n_distance = distance[&current] + grid.segment_cost(current, next);
+            if distance.contains_key(&next) && n_distance >= distance[&next] {
+                continue;
+            }
+            distance.insert(next, n_distance);
+            parent.insert(next, current);
+            let f = n_distance * g_factor + movement.heuristic(next, goal) * h_factor;
+            pq.push(next, -f);
+            stats.opened.push(next);
+        }
+    }
+
+    if !distance.contains_key(&goal) || start == goal {
+        print_distance(start, goal, None, movement, &terrain);
+        return None;
+    }
+
+    let mut jump_points = vec![goal];
+    while let Some(previous) = parent.get(jump_points.last().unwrap()) {
+        jump_points.push(*previous);
+    }
+    jump_points.reverse();
+    let mut moves: Vec<(i32, i32)> = vec![];
+    for pair in jump_points.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let step = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+        let mut current = from;
+        while current != to {
+            current = (current.0 + step.0, current.1 + step.1);
+            moves.push(step);
+        }
+    }
+    print_distance(start, goal, Some(&moves), movement, &terrain);
+    println!("Path: {:?}", moves);
+    Some(moves)
+}
@@ -1,17 +1,38 @@
 pub mod agent;
+pub mod animation;
+pub mod ara;
+pub mod belief;
+pub mod batch;
+pub mod bidirectional;
 pub mod board;
 pub mod cell;
+pub mod clock;
+pub mod decision;
+pub mod dstar;
+pub mod editor;
+pub mod events;
+pub mod exploration;
+pub mod export;
 pub mod factory;
+pub mod grid;
+pub mod hpa;
+pub mod hud;
+pub mod ida;
+pub mod jps;
+pub mod movement;
+pub mod overlay;
 pub mod params;
 pub mod path;
+pub mod planner;
+pub mod poi;
+pub mod report;
+pub mod rng;
+pub mod search;
+pub mod sim;
 pub mod terrain;
 pub mod tool;
+pub mod trajectory;
+pub mod view;
+pub mod weighted;
+pub mod world_event;
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn it_works() {
-        let result = 2 + 2;
-        assert_eq!(result, 4);
-    }
-}
@@ -0,0 +1,106 @@
+use crate::search::{h, valid};
+use crate::terrain::Terrain;
+
+const CARDINAL: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const OCTILE: [(i32, i32); 8] = [
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (0, -1),
+    (1, 1),
+    (-1, 1),
+    (-1, -1),
+    (1, -1),
+];
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Connectivity {
+    #[default]
+    Four,
+    Eight,
+}
+
+// Com 8 vizinhos os custos são multiplicados por `scale` para que o passo diagonal
+// (custo do terreno * diagonal_cost) continue sendo um inteiro.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Movement {
+    pub connectivity: Connectivity,
+    pub diagonal_cost: f32,
+    pub corner_cutting: bool,
+}
+
+impl Movement {
+    pub fn new(connectivity: Connectivity, diagonal_cost: f32, corner_cutting: bool) -> Self {
+        Self {
+            connectivity,
+            diagonal_cost,
+            corner_cutting,
+        }
+    }
+
+    pub fn offsets(&self) -> &'static [(i32, i32)] {
+        match self.connectivity {
+            Connectivity::Four => &CARDINAL,
+            Connectivity::Eight => &OCTILE,
+        }
+    }
+
+    pub fn scale(&self) -> usize {
+        match self.connectivity {
+            Connectivity::Four => 1,
+            Connectivity::Eight => 10,
+        }
+    }
+
+    pub fn step_cost(&self, (mx, my): (i32, i32), terrain: Terrain) -> usize {
+        let cost = terrain.cost() * self.scale();
+        if mx != 0 && my != 0 {
+            (cost as f64 * self.diagonal_cost as f64).round() as usize
+        } else {
+            cost
+        }
+    }
+
+    // Manhattan para 4 vizinhos, Octile para 8, ambas admissíveis com custo mínimo 1.
+    pub fn heuristic(&self, (ax, ay): (i32, i32), (bx, by): (i32, i32)) -> i32 {
+        match self.connectivity {
+            Connectivity::Four => h((ax, ay), (bx, by)),
+            Connectivity::Eight => {
+                let dx = (ax - bx).abs();
+                let dy = (ay - by).abs();
+                let straight = self.step_cost((1, 0), Terrain::Grass) as i32;
+                let diagonal = self.step_cost((1, 1), Terrain::Grass) as i32;
+                straight * dx.max(dy) + (diagonal - straight).min(straight) * dx.min(dy)
+            }
+        }
+    }
+
+    // Confere limites, obstáculo no destino e, se `corner_cutting` estiver
+    // desligado, se a diagonal não passa raspando por um obstáculo.
+    pub fn can_move<F>(
+        &self,
+        (x, y): (i32, i32),
+        (mx, my): (i32, i32),
+        width: i32,
+        height: i32,
+        terrain: F,
+    ) -> bool
+    where
+        F: Fn(i32, i32) -> Terrain,
+    {
+        let (nx, ny) = (x + mx, y + my);
+        if !valid(nx, ny, width, height) || !terrain(nx, ny).passable() {
+            return false;
+        }
+        if mx != 0 && my != 0 && !self.corner_cutting {
+            return terrain(x + mx, y).passable() && terrain(x, y + my).passable();
+        }
+        true
+    }
+}
+
+impl Default for Movement {
+    fn default() -> Self {
+        Self::new(Connectivity::Four, std::f32::consts::SQRT_2, false)
+    }
+}
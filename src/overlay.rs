@@ -0,0 +1,164 @@
+use crate::agent::Agent;
+use crate::board::Board;
+use crate::clock::SimulationClock;
+use crate::grid::{GridCoord, GridLayout};
+use crate::path::Path;
+use bevy::prelude::*;
+
+// Sobreposição da busca: células fechadas e abertas pela última busca, o caminho
+// que está sendo seguido e o alcance do sensor. Teclas:
+//   V liga/desliga tudo, O listas aberta/fechada, L caminho, R raio do sensor.
+#[derive(Default)]
+pub struct Overlay {
+    pub search: bool,
+    pub path: bool,
+    pub radius: bool,
+}
+
+#[derive(Component)]
+pub struct OverlaySprite;
+
+pub fn toggle_overlay(keys: Res<Input<KeyCode>>, mut overlay: ResMut<Overlay>) {
+    if keys.just_pressed(KeyCode::V) {
+        let visible = !(overlay.search || overlay.path || overlay.radius);
+        overlay.search = visible;
+        overlay.path = visible;
+        overlay.radius = visible;
+    }
+    if keys.just_pressed(KeyCode::O) {
+        overlay.search = !overlay.search;
+    }
+    if keys.just_pressed(KeyCode::L) {
+        overlay.path = !overlay.path;
+    }
+    if keys.just_pressed(KeyCode::R) {
+        overlay.radius = !overlay.radius;
+    }
+}
+
+fn spawn_tint(commands: &mut Commands, center: Vec2, size: Vec2, color: Color, z: f32) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            transform: Transform::from_xyz(center.x, center.y, z),
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(OverlaySprite);
+}
+
+// Redesenha só quando algo que aparece muda: as teclas, a janela, o caminho, o
+// agente (posição e última busca) ou o passo do relógio. Desligada, só apaga o
+// que ficou.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_overlay(
+    mut commands: Commands,
+    layout: Res<GridLayout>,
+    board: Res<Board>,
+    overlay: Res<Overlay>,
+    clock: Option<Res<SimulationClock>>,
+    follow_path: Res<Path>,
+    query: Query<&Agent>,
+    query_changed: Query<Entity, Changed<Agent>>,
+    query_sprites: Query<Entity, With<OverlaySprite>>,
+    mut last_expansion: Local<Option<usize>>,
+) {
+    // No modo passo a passo do relógio só aparecem as primeiras expansões.
+    let expansion = clock.and_then(|clock| clock.expansion);
+    if !(overlay.search || overlay.path || overlay.radius || expansion.is_some()) {
+        for entity in query_sprites.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    let changed = overlay.is_changed()
+        || layout.is_changed()
+        || follow_path.is_changed()
+        || query_changed.iter().next().is_some()
+        || *last_expansion != expansion;
+    if !changed {
+        return;
+    }
+    *last_expansion = expansion;
+
+    for entity in query_sprites.iter() {
+        commands.entity(entity).despawn();
+    }
+    let agent = match query.get_single() {
+        Ok(agent) => agent,
+        Err(_) => return,
+    };
+
+    let center = |x: i32, y: i32| layout.center(GridCoord::new(x as usize, y as usize));
+    let cell_size = layout.cell_size();
+    let (ax, ay) = agent.position();
+    let (ax, ay) = (ax as i32, ay as i32);
+
+    if overlay.radius {
+        let r = agent.radius() as i32;
+        for x in (ax - r).max(0)..=(ax + r).min(board.width as i32 - 1) {
+            for y in (ay - r).max(0)..=(ay + r).min(board.height as i32 - 1) {
+                let color = Color::rgba(1.0, 1.0, 1.0, 0.25);
+                spawn_tint(&mut commands, center(x, y), cell_size, color, 1.2);
+            }
+        }
+    }
+
+    if overlay.search || expansion.is_some() {
+        let search = agent.last_search();
+        let (closed_count, opened_count) = match expansion {
+            Some(k) if k < search.closed.len() => (k, search.marks[k]),
+            _ => (search.closed.len(), search.opened.len()),
+        };
+        let mut closed = vec![vec![false; board.height]; board.width];
+        for &(x, y) in &search.closed[..closed_count] {
+            closed[x as usize][y as usize] = true;
+        }
+        let mut drawn = vec![vec![false; board.height]; board.width];
+        let opened = &search.opened[..opened_count];
+        for &(x, y) in opened.iter().chain(search.closed[..closed_count].iter()) {
+            if drawn[x as usize][y as usize] {
+                continue;
+            }
+            drawn[x as usize][y as usize] = true;
+            let color = if closed[x as usize][y as usize] {
+                Color::rgba(0.9, 0.1, 0.1, 0.35)
+            } else {
+                Color::rgba(1.0, 0.9, 0.0, 0.35)
+            };
+            spawn_tint(&mut commands, center(x, y), cell_size, color, 1.4);
+        }
+    }
+
+    if overlay.path {
+        let (mut x, mut y) = (ax, ay);
+        for &(mx, my) in &follow_path.moves {
+            if (mx, my) == (0, 0) {
+                continue;
+            }
+            let from = center(x, y);
+            x += mx;
+            y += my;
+            let to = center(x, y);
+            let delta = to - from;
+            commands
+                .spawn_bundle(SpriteBundle {
+                    transform: Transform {
+                        translation: ((from + to) / 2.).extend(1.6),
+                        rotation: Quat::from_rotation_z(delta.y.atan2(delta.x)),
+                        ..default()
+                    },
+                    sprite: Sprite {
+                        color: Color::rgb(0.1, 0.1, 0.1),
+                        custom_size: Some(Vec2::new(delta.length(), 3.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(OverlaySprite);
+        }
+    }
+}
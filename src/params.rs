@@ -1,4 +1,26 @@
-use crate::tool::ToolType;
+use crate::ara::Budget;
+use crate::exploration::ExplorationPolicy;
+use crate::movement::Movement;
+use crate::planner::Planner;
+use crate::poi::DestinationPolicy;
+use crate::tool::{read_quantities, ToolType};
+
+// Ferramentas espalhadas e pedidas nos cenários de inputs/ que não vieram do
+// editor.
+pub const ITEMS_QUANTITY: [(ToolType, usize); 5] = [
+    (ToolType::Battery, 20),
+    (ToolType::WeldingArm, 10),
+    (ToolType::SuctionPump, 8),
+    (ToolType::CoolingDevice, 6),
+    (ToolType::PneumaticArm, 4),
+];
+pub const FACTORIES_NEEDS: [(ToolType, usize); 5] = [
+    (ToolType::Battery, 8),
+    (ToolType::WeldingArm, 5),
+    (ToolType::SuctionPump, 2),
+    (ToolType::CoolingDevice, 5),
+    (ToolType::PneumaticArm, 2),
+];
 
 pub struct Params {
     pub items_quantity: Vec<(ToolType, usize)>,
@@ -7,6 +29,18 @@ pub struct Params {
     pub h_factor: i32,
     pub g_factor: i32,
     pub input_idx: usize,
+    pub exploration: ExplorationPolicy,
+    pub fog_of_war: bool,
+    pub planner: Planner,
+    pub event_rate: f64,
+    pub movement: Movement,
+    pub cluster_size: i32,
+    pub epsilon: f32,
+    pub ida_limit: usize,
+    pub anytime_epsilon: f32,
+    pub anytime_step: f32,
+    pub anytime_budget: Budget,
+    pub destination: DestinationPolicy,
 }
 
 impl Params {
@@ -25,6 +59,29 @@ impl Params {
             h_factor,
             g_factor,
             input_idx,
+            exploration: ExplorationPolicy::default(),
+            fog_of_war: false,
+            planner: Planner::default(),
+            event_rate: 0.0,
+            movement: Movement::default(),
+            cluster_size: 10,
+            epsilon: 1.5,
+            ida_limit: 2_000_000,
+            anytime_epsilon: 3.0,
+            anytime_step: 0.5,
+            anytime_budget: Budget::default(),
+            destination: DestinationPolicy::default(),
         }
     }
+
+    // O cenário `input_idx` com raio 4 e A*. As quantidades gravadas pelo editor em
+    // inputs/items_<idx> e inputs/needs_<idx> substituem as de `ITEMS_QUANTITY` e
+    // `FACTORIES_NEEDS`.
+    pub fn for_scenario(input_idx: usize) -> Self {
+        let items_quantity = read_quantities(&format!("inputs/items_{}", input_idx))
+            .unwrap_or_else(|| ITEMS_QUANTITY.to_vec());
+        let factories_needs = read_quantities(&format!("inputs/needs_{}", input_idx))
+            .unwrap_or_else(|| FACTORIES_NEEDS.to_vec());
+        Self::new(items_quantity, factories_needs, 4, 1, 1, input_idx)
+    }
 }
@@ -1,10 +1,14 @@
 pub struct Path {
     pub moves: Vec<(i32, i32)>,
+    pub destination: Option<(usize, usize)>,
 }
 
 impl Path {
     pub fn new() -> Self {
-        Self { moves: vec![] }
+        Self {
+            moves: vec![],
+            destination: None,
+        }
     }
 }
 
@@ -0,0 +1,12 @@
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Planner {
+    #[default]
+    AStar,
+    DStarLite,
+    JumpPoint,
+    Hierarchical,
+    Weighted,
+    Bidirectional,
+    IterativeDeepening,
+    Anytime,
+}
@@ -0,0 +1,190 @@
+use crate::movement::Movement;
+use crate::terrain::Terrain;
+use priority_queue::PriorityQueue;
+use std::collections::HashMap;
+
+const INF: i64 = i64::MAX / 4;
+
+// Como o agente escolhe o próximo destino da fila.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum DestinationPolicy {
+    // Menor distância heurística, como no original.
+    #[default]
+    Heuristic,
+    // Menor distância real, lida do cache.
+    Cached,
+    // Primeiro destino da melhor ordem de visita da fila inteira.
+    Tour,
+}
+
+// Cache de distâncias até os pontos de interesse (ferramentas, fábricas). Para cada
+// ponto guarda um campo com o custo de qualquer célula até ele, calculado com um
+// Dijkstra de trás para frente. Assim a distância do agente até um ponto e entre dois
+// pontos viram só uma consulta. Qualquer mudança de terreno descarta os campos.
+pub struct PoiCache {
+    width: i32,
+    height: i32,
+    movement: Movement,
+    terrain: Vec<Vec<Terrain>>,
+    fields: HashMap<(i32, i32), Vec<i64>>,
+    pub computed: usize,
+}
+
+impl PoiCache {
+    pub fn new(width: i32, height: i32, movement: Movement) -> Self {
+        Self {
+            width,
+            height,
+            movement,
+            terrain: vec![vec![Terrain::Grass; height as usize]; width as usize],
+            fields: HashMap::new(),
+            computed: 0,
+        }
+    }
+
+    fn id(&self, (x, y): (i32, i32)) -> usize {
+        (x * self.height + y) as usize
+    }
+
+    // Atualiza a cópia do terreno. Retorna true se algo mudou e o cache foi limpo.
+    pub fn refresh<F>(&mut self, terrain: F) -> bool
+    where
+        F: Fn(i32, i32) -> Terrain,
+    {
+        let mut changed = false;
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let current = terrain(x, y);
+                if self.terrain[x as usize][y as usize] != current {
+                    self.terrain[x as usize][y as usize] = current;
+                    changed = true;
+                }
+            }
+        }
+        if changed && !self.fields.is_empty() {
+            println!(
+                "Terrain changed, clearing {} cached fields",
+                self.fields.len()
+            );
+            self.fields.clear();
+        }
+        changed
+    }
+
+    pub fn warm(&mut self, pois: &[(i32, i32)]) {
+        for poi in pois {
+            self.field(*poi);
+        }
+    }
+
+    fn field(&mut self, poi: (i32, i32)) -> &Vec<i64> {
+        if !self.fields.contains_key(&poi) {
+            let field = self.dijkstra(poi);
+            self.fields.insert(poi, field);
+            self.computed += 1;
+        }
+        &self.fields[&poi]
+    }
+
+    // Dijkstra a partir do ponto andando pelas arestas ao contrário: o vizinho
+    // `previous` chega em `current` pagando o terreno de `current`.
+    fn dijkstra(&self, poi: (i32, i32)) -> Vec<i64> {
+        let terrain = |x: i32, y: i32| self.terrain[x as usize][y as usize];
+        let mut distance = vec![INF; (self.width * self.height) as usize];
+        let mut pq = PriorityQueue::new();
+        distance[self.id(poi)] = 0;
+        pq.push(poi, 0);
+        while let Some((current, cost)) = pq.pop() {
+            let cost = -cost;
+            let entered = terrain(current.0, current.1);
+            for &(mx, my) in self.movement.offsets() {
+                if !self
+                    .movement
+                    .can_move(current, (mx, my), self.width, self.height, terrain)
+                {
+                    continue;
+                }
+                let previous = (current.0 + mx, current.1 + my);
+                let n_cost = cost + self.movement.step_cost((mx, my), entered) as i64;
+                let previous_id = self.id(previous);
+                if n_cost < distance[previous_id] {
+                    distance[previous_id] = n_cost;
+                    pq.push(previous, -n_cost);
+                }
+            }
+        }
+        distance
+    }
+
+    pub fn distance(&mut self, from: (i32, i32), poi: (i32, i32)) -> Option<i64> {
+        let id = self.id(from);
+        let distance = self.field(poi)[id];
+        if distance >= INF {
+            None
+        } else {
+            Some(distance)
+        }
+    }
+
+    // Ordem de visita dos pontos saindo de `start`: vizinho mais próximo seguido de
+    // 2-opt. Os custos não são simétricos, então cada troca recalcula o trecho todo.
+    pub fn tour(&mut self, start: (i32, i32), pois: &[(i32, i32)]) -> Vec<usize> {
+        let n = pois.len();
+        let mut cost = vec![vec![INF; n + 1]; n + 1];
+        for j in 0..n {
+            let field = self.field(pois[j]).clone();
+            cost[n][j] = field[self.id(start)];
+            for i in 0..n {
+                cost[i][j] = field[self.id(pois[i])];
+            }
+        }
+        let tour_cost = |order: &[usize]| {
+            let mut total = 0;
+            let mut previous = n;
+            for &next in order {
+                total += cost[previous][next].min(INF / (n as i64 + 1));
+                previous = next;
+            }
+            total
+        };
+
+        let mut order: Vec<usize> = vec![];
+        let mut visited = vec![false; n];
+        let mut previous = n;
+        for _ in 0..n {
+            let next = (0..n)
+                .filter(|j| !visited[*j])
+                .min_by_key(|j| cost[previous][*j])
+                .unwrap();
+            visited[next] = true;
+            order.push(next);
+            previous = next;
+        }
+
+        let mut best = tour_cost(&order);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..n {
+                for j in i + 1..n {
+                    order[i..=j].reverse();
+                    let candidate = tour_cost(&order);
+                    if candidate < best {
+                        best = candidate;
+                        improved = true;
+                    } else {
+                        order[i..=j].reverse();
+                    }
+                }
+            }
+        }
+        println!("Tour: {:?} (cost {})", order, best);
+        order
+    }
+}
+
+impl Default for PoiCache {
+    fn default() -> Self {
+        Self::new(42, 42, Movement::default())
+    }
+}
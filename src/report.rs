@@ -0,0 +1,831 @@
+use crate::batch::RunResult;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+// Uma linha do CSV do lote, com a estratégia pelo nome para aceitar arquivos de
+// versões com outras estratégias.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Record {
+    pub scenario: usize,
+    pub strategy: String,
+    pub repetition: usize,
+    pub seed: u64,
+    pub finished: bool,
+    pub cost: usize,
+    pub expansions: usize,
+    pub random_moves_cost: usize,
+    pub runtime_us: u64,
+}
+
+impl Record {
+    pub fn from_result(result: &RunResult) -> Self {
+        Self {
+            scenario: result.spec.scenario,
+            strategy: result.spec.strategy.name.to_string(),
+            repetition: result.spec.repetition,
+            seed: result.spec.seed,
+            finished: result.summary.finished,
+            cost: result.summary.cost,
+            expansions: result.summary.expansions,
+            random_moves_cost: result.summary.random_moves_cost,
+            runtime_us: result.runtime.as_micros() as u64,
+        }
+    }
+}
+
+// Lê o CSV de `batch::to_csv` pelo cabeçalho, então colunas a mais ou em outra
+// ordem não atrapalham.
+pub fn parse_csv(contents: &str) -> Vec<Record> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<&str> = lines
+        .next()
+        .expect("Something went wrong")
+        .split(',')
+        .collect();
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|c| c.trim() == name)
+            .expect("Something went wrong")
+    };
+    let scenario = column("scenario");
+    let strategy = column("strategy");
+    let repetition = column("repetition");
+    let seed = column("seed");
+    let finished = column("finished");
+    let cost = column("cost");
+    let expansions = column("expansions");
+    let random_moves_cost = column("random_moves_cost");
+    let runtime_us = column("runtime_us");
+
+    lines
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            Record {
+                scenario: fields[scenario].parse().unwrap(),
+                strategy: fields[strategy].to_string(),
+                repetition: fields[repetition].parse().unwrap(),
+                seed: fields[seed].parse().unwrap(),
+                finished: fields[finished].parse().unwrap(),
+                cost: fields[cost].parse().unwrap(),
+                expansions: fields[expansions].parse().unwrap(),
+                random_moves_cost: fields[random_moves_cost].parse().unwrap(),
+                runtime_us: fields[runtime_us].parse().unwrap(),
+            }
+        })
+        .collect()
+}
+
+// Em todas as métricas, menor é melhor.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Metric {
+    Cost,
+    Expansions,
+    RandomMovesCost,
+    Runtime,
+}
+
+pub const METRICS: [Metric; 4] = [
+    Metric::Cost,
+    Metric::Expansions,
+    Metric::RandomMovesCost,
+    Metric::Runtime,
+];
+
+impl Metric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metric::Cost => "cost",
+            Metric::Expansions => "expansions",
+            Metric::RandomMovesCost => "random_moves_cost",
+            Metric::Runtime => "runtime",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Metric::Cost => "Path cost",
+            Metric::Expansions => "Expansions",
+            Metric::RandomMovesCost => "Random move cost",
+            Metric::Runtime => "Runtime (µs)",
+        }
+    }
+
+    pub fn value(&self, record: &Record) -> f64 {
+        match self {
+            Metric::Cost => record.cost as f64,
+            Metric::Expansions => record.expansions as f64,
+            Metric::RandomMovesCost => record.random_moves_cost as f64,
+            Metric::Runtime => record.runtime_us as f64,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Distribution {
+    pub count: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    // Quartis com interpolação linear entre as posições ordenadas e desvio
+    // padrão amostral. `None` sem valores.
+    pub fn new(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let (mean, std_dev) = mean_std_dev(&sorted);
+        Some(Self {
+            count: sorted.len(),
+            mean,
+            std_dev,
+            min: sorted[0],
+            q1: quantile(&sorted, 0.25),
+            median: quantile(&sorted, 0.5),
+            q3: quantile(&sorted, 0.75),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+}
+
+fn mean_std_dev(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+// Quantil de 97,5% da t de Student, para intervalos de 95%. Acima de 30 graus de
+// liberdade usa a linha de baixo mais próxima, o que só alarga o intervalo.
+fn t_critical(degrees: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match degrees {
+        0 => f64::NAN,
+        1..=30 => TABLE[degrees - 1],
+        31..=39 => 2.042,
+        40..=59 => 2.021,
+        60..=119 => 2.000,
+        120..=999 => 1.980,
+        _ => 1.960,
+    }
+}
+
+// `a` contra `b` nas execuções que os dois terminaram com o mesmo cenário,
+// repetição e semente. Uma vitória é `a` com valor menor; a diferença é a - b.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Comparison {
+    pub a: String,
+    pub b: String,
+    pub metric: Metric,
+    pub pairs: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub ties: usize,
+    pub mean_difference: f64,
+    pub interval: Option<(f64, f64)>,
+}
+
+type RunKey = (usize, usize, u64);
+
+// Diferença média e intervalo de confiança, quando há pares para isso.
+type Difference = (f64, Option<(f64, f64)>);
+
+fn finished_runs(records: &[Record], strategy: &str) -> BTreeMap<RunKey, Record> {
+    records
+        .iter()
+        .filter(|r| r.strategy == strategy && r.finished)
+        .map(|r| ((r.scenario, r.repetition, r.seed), r.clone()))
+        .collect()
+}
+
+pub fn compare(records: &[Record], a: &str, b: &str, metric: Metric) -> Comparison {
+    let runs_a = finished_runs(records, a);
+    let runs_b = finished_runs(records, b);
+    let differences: Vec<f64> = runs_a
+        .iter()
+        .filter_map(|(key, run_a)| {
+            runs_b
+                .get(key)
+                .map(|run_b| metric.value(run_a) - metric.value(run_b))
+        })
+        .collect();
+    let (mean_difference, std_dev) = if differences.is_empty() {
+        (0.0, 0.0)
+    } else {
+        mean_std_dev(&differences)
+    };
+    let interval = if differences.len() < 2 {
+        None
+    } else {
+        let margin =
+            t_critical(differences.len() - 1) * std_dev / (differences.len() as f64).sqrt();
+        Some((mean_difference - margin, mean_difference + margin))
+    };
+    Comparison {
+        a: a.to_string(),
+        b: b.to_string(),
+        metric,
+        pairs: differences.len(),
+        wins: differences.iter().filter(|d| **d < 0.0).count(),
+        losses: differences.iter().filter(|d| **d > 0.0).count(),
+        ties: differences.iter().filter(|d| **d == 0.0).count(),
+        mean_difference,
+        interval,
+    }
+}
+
+// Distribuições por estratégia e comparações de todos os pares, para cada
+// métrica. Execuções que não terminaram entram só na contagem: o custo delas é
+// de um trajeto pela metade.
+pub struct Report {
+    pub runs: usize,
+    pub scenarios: Vec<usize>,
+    pub strategies: Vec<String>,
+    pub unfinished: Vec<usize>,
+    pub distributions: Vec<(Metric, Vec<Option<Distribution>>)>,
+    pub comparisons: Vec<Comparison>,
+}
+
+impl Report {
+    pub fn new(records: &[Record]) -> Self {
+        let mut strategies: Vec<String> = vec![];
+        let mut scenarios: Vec<usize> = vec![];
+        for record in records {
+            if !strategies.contains(&record.strategy) {
+                strategies.push(record.strategy.clone());
+            }
+            if !scenarios.contains(&record.scenario) {
+                scenarios.push(record.scenario);
+            }
+        }
+        scenarios.sort_unstable();
+        let unfinished = strategies
+            .iter()
+            .map(|s| {
+                records
+                    .iter()
+                    .filter(|r| r.strategy == *s && !r.finished)
+                    .count()
+            })
+            .collect();
+        let distributions = METRICS
+            .iter()
+            .map(|metric| {
+                let per_strategy = strategies
+                    .iter()
+                    .map(|s| {
+                        let values: Vec<f64> = records
+                            .iter()
+                            .filter(|r| r.strategy == *s && r.finished)
+                            .map(|r| metric.value(r))
+                            .collect();
+                        Distribution::new(&values)
+                    })
+                    .collect();
+                (*metric, per_strategy)
+            })
+            .collect();
+        let mut comparisons = vec![];
+        for metric in METRICS {
+            for (i, a) in strategies.iter().enumerate() {
+                for b in &strategies[i + 1..] {
+                    comparisons.push(compare(records, a, b, metric));
+                }
+            }
+        }
+        Self {
+            runs: records.len(),
+            scenarios,
+            strategies,
+            unfinished,
+            distributions,
+            comparisons,
+        }
+    }
+
+    fn distribution(&self, metric: Metric) -> &[Option<Distribution>] {
+        &self
+            .distributions
+            .iter()
+            .find(|(m, _)| *m == metric)
+            .unwrap()
+            .1
+    }
+
+    fn comparisons(&self, metric: Metric) -> impl Iterator<Item = &Comparison> {
+        self.comparisons.iter().filter(move |c| c.metric == metric)
+    }
+
+    // Diferença média e intervalo de `other` menos `baseline`, qualquer que seja a
+    // ordem em que o par foi comparado.
+    fn against(&self, metric: Metric, baseline: &str, other: &str) -> Option<Difference> {
+        self.comparisons(metric).find_map(|c| {
+            if c.pairs == 0 {
+                None
+            } else if c.a == other && c.b == baseline {
+                Some((c.mean_difference, c.interval))
+            } else if c.a == baseline && c.b == other {
+                Some((
+                    -c.mean_difference,
+                    c.interval.map(|(low, high)| (-high, -low)),
+                ))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+// Tabelas montadas uma vez e escritas em Markdown ou HTML.
+struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn markdown(&self) -> String {
+        let mut text = format!("| {} |\n", self.header.join(" | "));
+        text += &format!("|{}\n", " --- |".repeat(self.header.len()));
+        for row in &self.rows {
+            text += &format!("| {} |\n", row.join(" | "));
+        }
+        text
+    }
+
+    fn html(&self) -> String {
+        let mut text = String::from("<table>\n<tr>");
+        for cell in &self.header {
+            write!(text, "<th>{}</th>", escape(cell)).unwrap();
+        }
+        text += "</tr>\n";
+        for row in &self.rows {
+            text += "<tr>";
+            for cell in row {
+                write!(text, "<td>{}</td>", escape(cell)).unwrap();
+            }
+            text += "</tr>\n";
+        }
+        text + "</table>\n"
+    }
+}
+
+fn header(cells: &[&str]) -> Vec<String> {
+    cells.iter().map(|c| c.to_string()).collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn number(value: f64) -> String {
+    if value.is_nan() {
+        "-".to_string()
+    } else if value.abs() >= 100.0 || value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+fn runs_table(report: &Report) -> Table {
+    Table {
+        header: header(&["strategy", "unfinished runs"]),
+        rows: report
+            .strategies
+            .iter()
+            .zip(&report.unfinished)
+            .map(|(s, u)| vec![s.clone(), u.to_string()])
+            .collect(),
+    }
+}
+
+fn distribution_table(report: &Report, metric: Metric) -> Table {
+    let rows = report
+        .strategies
+        .iter()
+        .zip(report.distribution(metric))
+        .map(|(strategy, distribution)| {
+            let mut row = vec![strategy.clone()];
+            match distribution {
+                Some(d) => row.extend(
+                    [
+                        d.count as f64,
+                        d.mean,
+                        d.std_dev,
+                        d.min,
+                        d.q1,
+                        d.median,
+                        d.q3,
+                        d.max,
+                    ]
+                    .iter()
+                    .map(|v| number(*v)),
+                ),
+                None => row.extend(vec!["-".to_string(); 8]),
+            }
+            row
+        })
+        .collect();
+    Table {
+        header: header(&[
+            "strategy", "n", "mean", "std dev", "min", "q1", "median", "q3", "max",
+        ]),
+        rows,
+    }
+}
+
+fn comparison_table(report: &Report, metric: Metric) -> Table {
+    let rows = report
+        .comparisons(metric)
+        .map(|c| {
+            let interval = match c.interval {
+                Some((low, high)) => format!("[{}, {}]", number(low), number(high)),
+                None => "-".to_string(),
+            };
+            vec![
+                c.a.clone(),
+                c.b.clone(),
+                c.pairs.to_string(),
+                c.wins.to_string(),
+                c.losses.to_string(),
+                c.ties.to_string(),
+                number(c.mean_difference),
+                interval,
+            ]
+        })
+        .collect();
+    Table {
+        header: header(&[
+            "a",
+            "b",
+            "pairs",
+            "a wins",
+            "b wins",
+            "ties",
+            "mean a - b",
+            "95% CI",
+        ]),
+        rows,
+    }
+}
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 320.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 30.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+const COLOR: &str = "#3b6ea5";
+
+// Escala linear de [low, high] para [from, to], com uma folga quando o intervalo
+// é um ponto só.
+struct Scale {
+    low: f64,
+    high: f64,
+    from: f64,
+    to: f64,
+}
+
+impl Scale {
+    fn new(low: f64, high: f64, from: f64, to: f64) -> Self {
+        let (low, high) = if high > low {
+            (low, high)
+        } else {
+            (low - 1.0, high + 1.0)
+        };
+        Self {
+            low,
+            high,
+            from,
+            to,
+        }
+    }
+
+    fn map(&self, value: f64) -> f64 {
+        self.from + (value - self.low) / (self.high - self.low) * (self.to - self.from)
+    }
+
+    fn ticks(&self) -> Vec<f64> {
+        (0..=4)
+            .map(|i| self.low + (self.high - self.low) * i as f64 / 4.0)
+            .collect()
+    }
+}
+
+fn svg_start(title: &str) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+    writeln!(
+        svg,
+        "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+        CHART_WIDTH, CHART_HEIGHT
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "<text x=\"{}\" y=\"18\" text-anchor=\"middle\" font-size=\"14\">{}</text>",
+        CHART_WIDTH / 2.0,
+        escape(title)
+    )
+    .unwrap();
+    svg
+}
+
+// Caixa de q1 a q3 com a mediana, bigodes até o mínimo e o máximo e a média
+// como um ponto, uma caixa por estratégia.
+pub fn box_plot(report: &Report, metric: Metric) -> String {
+    let distributions = report.distribution(metric);
+    let present: Vec<&Distribution> = distributions.iter().flatten().collect();
+    let low = present.iter().map(|d| d.min).fold(f64::INFINITY, f64::min);
+    let high = present
+        .iter()
+        .map(|d| d.max)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let (low, high) = if present.is_empty() {
+        (0.0, 1.0)
+    } else {
+        (low, high)
+    };
+    let y = Scale::new(low, high, CHART_HEIGHT - MARGIN_BOTTOM, MARGIN_TOP);
+    let mut svg = svg_start(metric.title());
+
+    for tick in y.ticks() {
+        let ty = y.map(tick);
+        writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{ty:.1}\" x2=\"{}\" y2=\"{ty:.1}\" stroke=\"#ddd\"/>",
+            MARGIN_LEFT,
+            CHART_WIDTH - MARGIN_RIGHT
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            MARGIN_LEFT - 6.0,
+            ty + 4.0,
+            number(tick)
+        )
+        .unwrap();
+    }
+
+    let slot = (CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT) / report.strategies.len().max(1) as f64;
+    let half = (slot * 0.3).min(30.0);
+    for (i, (strategy, distribution)) in report.strategies.iter().zip(distributions).enumerate() {
+        let cx = MARGIN_LEFT + slot * (i as f64 + 0.5);
+        writeln!(
+            svg,
+            "<text x=\"{cx:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            CHART_HEIGHT - MARGIN_BOTTOM + 18.0,
+            escape(strategy)
+        )
+        .unwrap();
+        let d = match distribution {
+            Some(d) => d,
+            None => continue,
+        };
+        writeln!(
+            svg,
+            "<line x1=\"{cx:.1}\" y1=\"{:.1}\" x2=\"{cx:.1}\" y2=\"{:.1}\" stroke=\"black\"/>",
+            y.map(d.min),
+            y.map(d.max)
+        )
+        .unwrap();
+        for whisker in [d.min, d.max] {
+            writeln!(
+                svg,
+                "<line x1=\"{:.1}\" y1=\"{wy:.1}\" x2=\"{:.1}\" y2=\"{wy:.1}\" stroke=\"black\"/>",
+                cx - half / 2.0,
+                cx + half / 2.0,
+                wy = y.map(whisker)
+            )
+            .unwrap();
+        }
+        writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" fill-opacity=\"0.4\" stroke=\"black\"/>",
+            cx - half,
+            y.map(d.q3),
+            half * 2.0,
+            (y.map(d.q1) - y.map(d.q3)).max(1.0),
+            COLOR
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<line x1=\"{:.1}\" y1=\"{my:.1}\" x2=\"{:.1}\" y2=\"{my:.1}\" stroke=\"black\" stroke-width=\"2\"/>",
+            cx - half,
+            cx + half,
+            my = y.map(d.median)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<circle cx=\"{cx:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"white\" stroke=\"black\"/>",
+            y.map(d.mean)
+        )
+        .unwrap();
+    }
+    svg + "</svg>\n"
+}
+
+// Diferença média de cada estratégia para `baseline`, com o intervalo de 95%.
+// À esquerda do zero a estratégia fica abaixo da referência.
+pub fn interval_plot(report: &Report, metric: Metric, baseline: &str) -> String {
+    let rows: Vec<(&String, Difference)> = report
+        .strategies
+        .iter()
+        .filter(|s| *s != baseline)
+        .filter_map(|s| {
+            report
+                .against(metric, baseline, s)
+                .map(|difference| (s, difference))
+        })
+        .collect();
+    let mut low: f64 = 0.0;
+    let mut high: f64 = 0.0;
+    for (_, (mean, interval)) in &rows {
+        let (a, b) = interval.unwrap_or((*mean, *mean));
+        low = low.min(a);
+        high = high.max(b);
+    }
+    let x = Scale::new(low, high, MARGIN_LEFT + 40.0, CHART_WIDTH - MARGIN_RIGHT);
+    let title = format!("{}: difference to {}", metric.title(), baseline);
+    let mut svg = svg_start(&title);
+
+    let bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+    for tick in x.ticks() {
+        let tx = x.map(tick);
+        writeln!(
+            svg,
+            "<line x1=\"{tx:.1}\" y1=\"{}\" x2=\"{tx:.1}\" y2=\"{}\" stroke=\"#ddd\"/>",
+            MARGIN_TOP, bottom
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<text x=\"{tx:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            bottom + 18.0,
+            number(tick)
+        )
+        .unwrap();
+    }
+    let zero = x.map(0.0);
+    writeln!(
+        svg,
+        "<line x1=\"{zero:.1}\" y1=\"{}\" x2=\"{zero:.1}\" y2=\"{}\" stroke=\"black\" stroke-dasharray=\"4 3\"/>",
+        MARGIN_TOP, bottom
+    )
+    .unwrap();
+
+    let slot = (bottom - MARGIN_TOP) / rows.len().max(1) as f64;
+    for (i, (strategy, (mean, interval))) in rows.iter().enumerate() {
+        let cy = MARGIN_TOP + slot * (i as f64 + 0.5);
+        writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            MARGIN_LEFT + 30.0,
+            cy + 4.0,
+            escape(strategy)
+        )
+        .unwrap();
+        if let Some((a, b)) = interval {
+            writeln!(
+                svg,
+                "<line x1=\"{:.1}\" y1=\"{cy:.1}\" x2=\"{:.1}\" y2=\"{cy:.1}\" stroke=\"{}\" stroke-width=\"3\"/>",
+                x.map(*a),
+                x.map(*b),
+                COLOR
+            )
+            .unwrap();
+        }
+        writeln!(
+            svg,
+            "<circle cx=\"{:.1}\" cy=\"{cy:.1}\" r=\"4\" fill=\"black\"/>",
+            x.map(*mean)
+        )
+        .unwrap();
+    }
+    svg + "</svg>\n"
+}
+
+fn intro(report: &Report) -> String {
+    let scenarios: Vec<String> = report.scenarios.iter().map(|s| s.to_string()).collect();
+    format!(
+        "{} runs of {} strategies on scenarios {}. Distributions use finished runs only. \
+         Comparisons pair runs of the same scenario, repetition and seed that both \
+         strategies finished; lower is better, so \"a wins\" counts pairs where a is lower.",
+        report.runs,
+        report.strategies.len(),
+        scenarios.join(", ")
+    )
+}
+
+// Markdown que aponta para os SVG gravados ao lado por `write_report`.
+pub fn markdown(report: &Report) -> String {
+    let mut text = format!("# Strategy comparison\n\n{}\n\n", intro(report));
+    text += &runs_table(report).markdown();
+    let baseline = report.strategies.first();
+    for metric in METRICS {
+        write!(
+            text,
+            "\n## {}\n\n![{}]({}_box.svg)\n\n",
+            metric.title(),
+            metric.name(),
+            metric.name()
+        )
+        .unwrap();
+        text += &distribution_table(report, metric).markdown();
+        text += "\n";
+        if let Some(baseline) = baseline {
+            writeln!(
+                text,
+                "![{} vs {}]({}_diff.svg)\n",
+                metric.name(),
+                baseline,
+                metric.name()
+            )
+            .unwrap();
+        }
+        text += &comparison_table(report, metric).markdown();
+    }
+    text
+}
+
+// Página única, com os gráficos embutidos.
+pub fn html(report: &Report) -> String {
+    let mut text = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Strategy comparison</title>\n\
+         <style>body{font-family:sans-serif;margin:2em}table{border-collapse:collapse;margin:1em 0}\
+         td,th{border:1px solid #ccc;padding:2px 8px;text-align:right}</style>\n</head>\n<body>\n",
+    );
+    write!(
+        text,
+        "<h1>Strategy comparison</h1>\n<p>{}</p>\n",
+        escape(&intro(report))
+    )
+    .unwrap();
+    text += &runs_table(report).html();
+    let baseline = report.strategies.first();
+    for metric in METRICS {
+        writeln!(text, "<h2>{}</h2>", escape(metric.title())).unwrap();
+        text += &box_plot(report, metric);
+        text += &distribution_table(report, metric).html();
+        if let Some(baseline) = baseline {
+            text += &interval_plot(report, metric, baseline);
+        }
+        text += &comparison_table(report, metric).html();
+    }
+    text + "</body>\n</html>\n"
+}
+
+// Grava report.md, report.html e os gráficos de cada métrica em `dir`.
+pub fn write_report(report: &Report, dir: &str) {
+    let dir = Path::new(dir);
+    fs::create_dir_all(dir).expect("Something went wrong");
+    fs::write(dir.join("report.md"), markdown(report)).expect("Something went wrong");
+    fs::write(dir.join("report.html"), html(report)).expect("Something went wrong");
+    let baseline = report.strategies.first();
+    for metric in METRICS {
+        fs::write(
+            dir.join(format!("{}_box.svg", metric.name())),
+            box_plot(report, metric),
+        )
+        .expect("Something went wrong");
+        if let Some(baseline) = baseline {
+            fs::write(
+                dir.join(format!("{}_diff.svg", metric.name())),
+                interval_plot(report, metric, baseline),
+            )
+            .expect("Something went wrong");
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::ops::{Deref, DerefMut};
+
+// Gerador com semente conhecida, para que uma execução possa ser repetida. Todo
+// sorteio da simulação (passeio aleatório, eventos do mundo) passa por ele, e a
+// semente vai para o cabeçalho do registro de trajetória.
+pub struct SimRng {
+    pub seed: u64,
+    rng: StdRng,
+}
+
+impl SimRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl Deref for SimRng {
+    type Target = StdRng;
+
+    fn deref(&self) -> &StdRng {
+        &self.rng
+    }
+}
+
+impl DerefMut for SimRng {
+    fn deref_mut(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
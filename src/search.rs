@@ -0,0 +1,170 @@
+use crate::decision::path_cost;
+use crate::movement::Movement;
+use crate::terrain::Terrain;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+pub fn h((ax, ay): (i32, i32), (bx, by): (i32, i32)) -> i32 {
+    (ax - bx).abs() + (ay - by).abs()
+}
+
+// Contadores de uma busca: nós expandidos e o maior tamanho que a lista aberta
+// chegou a ter. `opened` e `closed` guardam as células por onde a busca passou,
+// para a sobreposição desenhar; `marks[i]` é quantas células já tinham sido
+// abertas quando a expansão i começou.
+#[derive(Clone, Debug, Default)]
+pub struct SearchStats {
+    pub expansions: usize,
+    pub peak_open: usize,
+    pub opened: Vec<(i32, i32)>,
+    pub closed: Vec<(i32, i32)>,
+    pub marks: Vec<usize>,
+}
+
+impl SearchStats {
+    pub fn expand(&mut self, open: usize) {
+        self.expansions += 1;
+        self.peak_open = self.peak_open.max(open);
+    }
+
+    pub fn close(&mut self, cell: (i32, i32)) {
+        self.marks.push(self.opened.len());
+        self.closed.push(cell);
+    }
+
+    pub fn add(&mut self, other: &SearchStats) {
+        self.expansions += other.expansions;
+        self.peak_open = self.peak_open.max(other.peak_open);
+    }
+}
+
+// A linha que todos os planejadores imprimem: o custo do caminho devolvido, somado
+// passo a passo com `path_cost`, para que os números dos planejadores possam ser
+// comparados entre si; "-" quando não há caminho.
+pub fn print_distance<F>(
+    start: (i32, i32),
+    goal: (i32, i32),
+    moves: Option<&[(i32, i32)]>,
+    movement: &Movement,
+    terrain: F,
+) where
+    F: Fn(i32, i32) -> Terrain,
+{
+    let distance = match moves {
+        Some(moves) => path_cost(start, moves, movement, terrain).to_string(),
+        None => "-".to_string(),
+    };
+    println!(
+        "\nDistance between ({}, {}) and ({}, {}): {}",
+        start.0, start.1, goal.0, goal.1, distance
+    );
+}
+
+pub fn valid(x: i32, y: i32, width: i32, height: i32) -> bool {
+    if x < 0 {
+        return false;
+    }
+    if y < 0 {
+        return false;
+    }
+    if x >= width {
+        return false;
+    }
+    if y >= height {
+        return false;
+    }
+    true
+}
+
+// Busca com custo g * g_factor + h * h_factor, onde o terreno de cada célula é
+// consultado através de `terrain`. Quem chama decide se o mapa consultado é o
+// mundo real ou a crença do agente.
+//
+// O custo de um nó soma, a cada passo, o custo do terreno e a heurística da célula
+// de destino, como sempre foi. O estado fica em vetores indexados por
+// x * height + y e a fila é um heap binário com remoção preguiçosa: uma célula
+// só entra de novo se chegar mais barata que o melhor custo com que já entrou,
+// e as entradas antigas, mais caras, são descartadas quando saem. Por isso uma
+// célula fechada é reaberta quando aparece um caminho mais barato até ela. O
+// caminho de cada entrada é um nó com o nó pai e o movimento, e só é montado no
+// fim. Nos empates sai primeiro quem entrou por último.
+#[allow(clippy::too_many_arguments)]
+pub fn a_star<F>(
+    (ax, ay): (i32, i32),
+    (dx, dy): (i32, i32),
+    width: i32,
+    height: i32,
+    terrain: F,
+    movement: &Movement,
+    g_factor: i32,
+    h_factor: i32,
+    stats: &mut SearchStats,
+) -> Option<Vec<(i32, i32)>>
+where
+    F: Fn(i32, i32) -> Terrain,
+{
+    let size = (width * height) as usize;
+    let id = |(x, y): (i32, i32)| (x * height + y) as usize;
+    let cell = |id: usize| (id as i32 / height, id as i32 % height);
+    let mut open: BinaryHeap<(Reverse<i64>, usize, usize)> = BinaryHeap::new();
+    // Melhor custo com que a célula entrou na fila. Um caminho pior que chega
+    // depois não pode sobrescrever o caminho guardado.
+    let mut best: Vec<i64> = vec![i64::MAX; size];
+    let mut node_of: Vec<usize> = vec![usize::MAX; size];
+    let mut nodes: Vec<(usize, (i32, i32))> = vec![];
+    let start = id((ax, ay));
+    let goal = id((dx, dy));
+    let mut pushed = 0;
+    let mut found = false;
+
+    best[start] = 0;
+    open.push((Reverse(0), pushed, start));
+    while let Some((Reverse(cost), _, current)) = open.pop() {
+        if cost > best[current] {
+            continue;
+        }
+        if current == goal {
+            found = true;
+            break;
+        }
+        stats.expand(open.len() + 1);
+        let (cx, cy) = cell(current);
+        stats.close((cx, cy));
+        for &(mx, my) in movement.offsets() {
+            if !movement.can_move((cx, cy), (mx, my), width, height, &terrain) {
+                continue;
+            }
+            let (nx, ny) = (cx + mx, cy + my);
+            let next = id((nx, ny));
+            let g = movement.step_cost((mx, my), terrain(nx, ny)) as i64;
+            let h = movement.heuristic((nx, ny), (dx, dy)) as i64;
+            let n_cost = cost + g * g_factor as i64 + h * h_factor as i64;
+            if n_cost >= best[next] {
+                continue;
+            }
+            best[next] = n_cost;
+            nodes.push((node_of[current], (mx, my)));
+            node_of[next] = nodes.len() - 1;
+            pushed += 1;
+            open.push((Reverse(n_cost), pushed, next));
+            stats.opened.push((nx, ny));
+        }
+    }
+
+    if !found || node_of[goal] == usize::MAX {
+        print_distance((ax, ay), (dx, dy), None, movement, &terrain);
+        return None;
+    }
+
+    let mut moves: Vec<(i32, i32)> = vec![];
+    let mut node = node_of[goal];
+    while node != usize::MAX {
+        let (parent, step) = nodes[node];
+        moves.push(step);
+        node = parent;
+    }
+    moves.reverse();
+    print_distance((ax, ay), (dx, dy), Some(&moves), movement, &terrain);
+    println!("Path: {:?}", moves);
+    Some(moves)
+}
@@ -0,0 +1,374 @@
+use crate::agent::place_agent;
+use crate::board::{read_field, terrain_grid};
+use crate::decision::*;
+use crate::events::{Observers, RunFinished, SimEvent};
+use crate::exploration::ExplorationPolicy;
+use crate::factory::{place_factories, Factory};
+use crate::params::Params;
+use crate::planner::Planner;
+use crate::poi::{DestinationPolicy, PoiCache};
+use crate::rng::SimRng;
+use crate::search::SearchStats;
+use crate::terrain::Terrain;
+use crate::tool::{place_tools, ToolType};
+use crate::trajectory::{TrajectoryEvent, TrajectoryLog};
+use std::fmt;
+
+// Números do fim de uma execução, os mesmos que os exemplos imprimem. Custos sem a
+// escala de `Movement`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RunSummary {
+    pub finished: bool,
+    pub ticks: usize,
+    pub cost: usize,
+    pub expansions: usize,
+    pub peak_open: usize,
+    pub random_moves: usize,
+    pub random_moves_cost: usize,
+}
+
+// A execução de `move_agent` e `follow_path` sem Bevy, com as mesmas regras de
+// `decision` e a mesma sequência de sorteios para uma semente.
+// Cada `tick` faz o que um quadro da simulação faz. O agente vê o mapa inteiro;
+// neblina, exploração por fronteiras, eventos do mundo e o ARA* ficam de fora.
+pub struct Simulation {
+    pub width: usize,
+    pub height: usize,
+    pub terrain: Vec<Vec<Terrain>>,
+    pub tools: Vec<Vec<Option<ToolType>>>,
+    pub factories: Vec<Vec<Option<Factory>>>,
+    pub params: Params,
+    pub position: (usize, usize),
+    pub state: Vec<(ToolType, usize)>,
+    pub requisitions: Vec<Factory>,
+    pub destination_queue: Vec<(usize, usize)>,
+    pub moves: Vec<(i32, i32)>,
+    pub destination: Option<(usize, usize)>,
+    pub cost: usize,
+    pub stats: SearchStats,
+    pub random_moves: usize,
+    pub random_moves_cost: usize,
+    pub ticks: usize,
+    pub ended: bool,
+    pub log: TrajectoryLog,
+    pub observers: Observers,
+    last_move: (i32, i32),
+    rng: SimRng,
+    planners: Planners,
+    poi_cache: PoiCache,
+}
+
+// O que os `Params` pedem e a `Simulation` não sabe fazer.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Unsupported {
+    FogOfWar,
+    WorldEvents,
+    Exploration,
+    Anytime,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self {
+            Unsupported::FogOfWar => "fog of war",
+            Unsupported::WorldEvents => "world events",
+            Unsupported::Exploration => "frontier exploration",
+            Unsupported::Anytime => "the anytime planner",
+        };
+        write!(f, "Headless runs do not support {}", what)
+    }
+}
+
+impl Simulation {
+    pub fn new(
+        terrain: Vec<Vec<Terrain>>,
+        tools: &[(usize, usize, ToolType)],
+        factories: &[Factory],
+        position: (usize, usize),
+        params: Params,
+        seed: u64,
+    ) -> Result<Self, Unsupported> {
+        if params.fog_of_war {
+            return Err(Unsupported::FogOfWar);
+        }
+        if params.event_rate != 0.0 {
+            return Err(Unsupported::WorldEvents);
+        }
+        if params.exploration != ExplorationPolicy::RandomWalk {
+            return Err(Unsupported::Exploration);
+        }
+        if params.planner == Planner::Anytime {
+            return Err(Unsupported::Anytime);
+        }
+        let width = terrain.len();
+        let height = terrain[0].len();
+        let mut tool_grid = vec![vec![None; height]; width];
+        for &(x, y, tool) in tools {
+            tool_grid[x][y] = Some(tool);
+        }
+        let mut factory_grid = vec![vec![None; height]; width];
+        for factory in factories {
+            factory_grid[factory.x][factory.y] = Some(*factory);
+        }
+        let mut log = TrajectoryLog {
+            input_idx: params.input_idx,
+            seed,
+            movement: params.movement,
+            ..TrajectoryLog::default()
+        };
+        log.take_pending();
+        let poi_cache = PoiCache::new(width as i32, height as i32, params.movement);
+        Ok(Self {
+            width,
+            height,
+            terrain,
+            tools: tool_grid,
+            factories: factory_grid,
+            params,
+            position,
+            state: empty_inventory(),
+            requisitions: factories.to_vec(),
+            destination_queue: vec![],
+            moves: vec![],
+            destination: None,
+            cost: 0,
+            stats: SearchStats::default(),
+            random_moves: 0,
+            random_moves_cost: 0,
+            ticks: 0,
+            ended: false,
+            log,
+            observers: Observers::default(),
+            last_move: (0, 0),
+            rng: SimRng::new(seed),
+            planners: Planners::default(),
+            poi_cache,
+        })
+    }
+
+    // O cenário `params.input_idx` de fields/ e inputs/, montado como em
+    // `setup_board`, `spawn_tools`, `spawn_factories` e `setup_agent`.
+    pub fn scenario(
+        width: usize,
+        height: usize,
+        params: Params,
+        seed: u64,
+    ) -> Result<Self, Unsupported> {
+        let idx = params.input_idx;
+        let terrain = terrain_grid(&read_field(idx), width, height);
+        let tools = place_tools(idx, &params.items_quantity, |x, y| terrain[x][y]);
+        let has_tool = |x: usize, y: usize| tools.iter().any(|t| t.x == x && t.y == y);
+        let factories = place_factories(idx, &params.factories_needs, |x, y| {
+            terrain[x][y].passable() && !has_tool(x, y)
+        });
+        let position = place_agent(idx, |x, y| {
+            terrain[x][y].passable()
+                && !has_tool(x, y)
+                && !factories.iter().any(|f| f.x == x && f.y == y)
+        });
+        let tools: Vec<(usize, usize, ToolType)> = tools
+            .iter()
+            .map(|t| (t.x, t.y, t.tool_type.unwrap()))
+            .collect();
+        Self::new(terrain, &tools, &factories, position, params, seed)
+    }
+
+    // Os observadores são chamados aqui mesmo; sem `publish_events` ninguém tiraria
+    // os eventos de `pending`, que cresceria a execução inteira.
+    fn emit(&mut self, event: TrajectoryEvent) {
+        self.log.push(event);
+        self.log.pending.clear();
+        if let Some(event) = SimEvent::from_trajectory(event) {
+            self.observers.notify(&event);
+        }
+    }
+
+    fn pickup(&mut self) {
+        let (x, y) = self.position;
+        if let Some(tool) = self.tools[x][y].take() {
+            self.emit(TrajectoryEvent::Pickup { x, y, tool });
+            self.state[tool_index(tool)].1 += 1;
+        }
+    }
+
+    fn check_radius(&mut self) {
+        let (ax, ay) = (self.position.0 as i32, self.position.1 as i32);
+        let tools = &self.tools;
+        check_radius(
+            (ax, ay),
+            self.params.agent_radius as i32,
+            self.width,
+            self.height,
+            &self.requisitions,
+            &mut self.destination_queue,
+            |x, y| tools[x][y],
+        );
+    }
+
+    // A parte de `move_agent`: coleta, confere se acabou, escolhe o próximo destino
+    // e planeja, ou dá um passo aleatório se a fila está vazia.
+    fn decide(&mut self) {
+        self.pickup();
+        if check_requisitions(&self.state, &self.requisitions, &mut self.destination_queue) {
+            if !self.ended {
+                self.ended = true;
+                let event = SimEvent::RunFinished(RunFinished {
+                    cost: self.cost,
+                    expansions: self.stats.expansions,
+                    random_moves: self.random_moves,
+                    random_moves_cost: self.random_moves_cost,
+                });
+                self.observers.notify(&event);
+            }
+            return;
+        }
+        self.check_radius();
+
+        let (width, height) = (self.width as i32, self.height as i32);
+        let grid = &self.terrain;
+        let terrain = |x: i32, y: i32| grid[x as usize][y as usize];
+        if self.params.destination != DestinationPolicy::Heuristic
+            && !self.destination_queue.is_empty()
+        {
+            self.poi_cache.refresh(terrain);
+        }
+
+        let (ax, ay) = (self.position.0 as i32, self.position.1 as i32);
+        if !self.destination_queue.is_empty() {
+            let next_idx = next_destination(
+                self.position,
+                &self.destination_queue,
+                &self.params,
+                &mut self.poi_cache,
+            );
+            let (dx, dy) = self.destination_queue.remove(next_idx);
+            if should_find_path(
+                self.tools[dx][dy],
+                self.factories[dx][dy],
+                &self.requisitions,
+            ) {
+                let mut stats = SearchStats::default();
+                let goal = (dx as i32, dy as i32);
+                let params = &self.params;
+                let path =
+                    self.planners
+                        .plan((ax, ay), goal, width, height, terrain, params, &mut stats);
+                let cost = path
+                    .as_ref()
+                    .map(|moves| path_cost((ax, ay), moves, &params.movement, terrain));
+                self.emit(TrajectoryEvent::Plan {
+                    from: self.position,
+                    to: (dx, dy),
+                    cost,
+                });
+                self.stats.add(&stats);
+                self.destination = Some((dx, dy));
+                match path {
+                    Some(moves) => self.moves = moves,
+                    None => self.moves.push((0, 0)),
+                }
+            }
+            return;
+        }
+
+        let movement = self.params.movement;
+        let step = random_step(
+            (ax, ay),
+            self.last_move,
+            width,
+            height,
+            &movement,
+            terrain,
+            &mut *self.rng,
+        );
+        if let Some((mx, my)) = step {
+            self.last_move = (-mx, -my);
+            let (x, y) = ((ax + mx) as usize, (ay + my) as usize);
+            self.position = (x, y);
+            let cost = movement.step_cost((mx, my), self.terrain[x][y]);
+            self.random_moves_cost += cost;
+            self.random_moves += 1;
+            self.emit(TrajectoryEvent::Move {
+                x,
+                y,
+                cost,
+                random: true,
+            });
+        }
+    }
+
+    // A parte de `follow_path`: um passo do caminho, com coleta e entrega.
+    fn step(&mut self) {
+        let (mx, my) = self.moves.remove(0);
+        if (mx, my) == (0, 0) {
+            return;
+        }
+        let (x, y) = (
+            (self.position.0 as i32 + mx) as usize,
+            (self.position.1 as i32 + my) as usize,
+        );
+        self.position = (x, y);
+        self.check_radius();
+
+        let cost = self.params.movement.step_cost((mx, my), self.terrain[x][y]);
+        self.cost += cost;
+        self.emit(TrajectoryEvent::Move {
+            x,
+            y,
+            cost,
+            random: false,
+        });
+        self.pickup();
+
+        // Como em `follow_path`, a fábrica do mapa guarda a requisição original.
+        if let Some(factory) = self.factories[x][y] {
+            let delivered = visit_factory(
+                &factory,
+                &mut self.state,
+                &mut self.requisitions,
+                &mut self.destination_queue,
+            );
+            if let Some((tool, quantity)) = delivered {
+                self.emit(TrajectoryEvent::Drop {
+                    x,
+                    y,
+                    tool,
+                    quantity,
+                });
+                self.emit(TrajectoryEvent::Deliver { x, y });
+            }
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.ended {
+            return;
+        }
+        self.ticks += 1;
+        if self.moves.is_empty() {
+            self.decide();
+        } else {
+            self.step();
+        }
+    }
+
+    // Roda até o agente terminar ou até `max_ticks`.
+    pub fn run(&mut self, max_ticks: usize) -> RunSummary {
+        while !self.ended && self.ticks < max_ticks {
+            self.tick();
+        }
+        self.summary()
+    }
+
+    pub fn summary(&self) -> RunSummary {
+        RunSummary {
+            finished: self.ended,
+            ticks: self.ticks,
+            cost: self.cost,
+            expansions: self.stats.expansions,
+            peak_open: self.stats.peak_open,
+            random_moves: self.random_moves,
+            random_moves_cost: self.random_moves_cost,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Terrain {
     Grass,
     Mountain,
@@ -6,3 +6,32 @@ pub enum Terrain {
     Desert,
     Obstacle,
 }
+
+impl Terrain {
+    pub fn cost(&self) -> usize {
+        match self {
+            Terrain::Grass => 1,
+            Terrain::Mountain => 5,
+            Terrain::Swamp => 10,
+            Terrain::Desert => 20,
+            Terrain::Obstacle => 1e9 as usize,
+        }
+    }
+
+    pub fn passable(&self) -> bool {
+        !matches!(self, Terrain::Obstacle)
+    }
+}
+
+// Aceita o número de fields/field.txt ou o nome usado nos eventos e no registro de
+// trajetória.
+pub fn parse_terrain(value: &str) -> Option<Terrain> {
+    match value {
+        "0" | "grass" => Some(Terrain::Grass),
+        "1" | "mountain" => Some(Terrain::Mountain),
+        "2" | "swamp" => Some(Terrain::Swamp),
+        "3" | "desert" => Some(Terrain::Desert),
+        "4" | "obstacle" => Some(Terrain::Obstacle),
+        _ => None,
+    }
+}
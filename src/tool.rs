@@ -1,5 +1,6 @@
 use crate::board::Board;
 use crate::cell::Cell;
+use crate::grid::{GridCoord, GridLayout};
 use crate::params::Params;
 use crate::terrain::Terrain;
 use bevy::prelude::*;
@@ -27,6 +28,61 @@ impl Tool {
     }
 }
 
+// Nomes das ferramentas nos arquivos de texto: eventos, registro de trajetória e
+// inputs/items_<idx> e inputs/needs_<idx>.
+pub fn tool_name(tool: ToolType) -> &'static str {
+    match tool {
+        ToolType::Battery => "battery",
+        ToolType::WeldingArm => "welding",
+        ToolType::SuctionPump => "pump",
+        ToolType::CoolingDevice => "cooling",
+        ToolType::PneumaticArm => "pneumatic",
+    }
+}
+
+pub fn parse_tool(value: &str) -> Option<ToolType> {
+    match value {
+        "battery" => Some(ToolType::Battery),
+        "welding" => Some(ToolType::WeldingArm),
+        "pump" => Some(ToolType::SuctionPump),
+        "cooling" => Some(ToolType::CoolingDevice),
+        "pneumatic" => Some(ToolType::PneumaticArm),
+        _ => None,
+    }
+}
+
+// Uma linha `<ferramenta> <quantidade>` por entrada, como `items_quantity` e
+// `factories_needs` de `Params`.
+pub fn quantities_text(quantities: &[(ToolType, usize)]) -> String {
+    let mut text = String::new();
+    for (tool, quantity) in quantities {
+        text += &format!("{} {}\n", tool_name(*tool), quantity);
+    }
+    text
+}
+
+pub fn parse_quantities(contents: &str) -> Vec<(ToolType, usize)> {
+    let mut data: Vec<(ToolType, usize)> = vec![];
+    for line in contents.lines() {
+        let values: Vec<&str> = line.split_whitespace().collect();
+        if values.is_empty() {
+            continue;
+        }
+        if values.len() != 2 {
+            panic!("Invalid quantity line: {}", line);
+        }
+        let tool = parse_tool(values[0]).expect("Unknown tool");
+        data.push((tool, values[1].parse::<usize>().unwrap()));
+    }
+    data
+}
+
+// `None` se o arquivo não existe, como nos cenários que não vieram do editor.
+pub fn read_quantities(path: &str) -> Option<Vec<(ToolType, usize)>> {
+    let contents = fs::read_to_string(path).ok()?;
+    Some(parse_quantities(&contents))
+}
+
 fn read_tools(idx: usize) -> Vec<(usize, usize)> {
     let mut data: Vec<(usize, usize)> = Vec::<(usize, usize)>::default();
     let field_path = "inputs/tools_".to_string() + &idx.to_string();
@@ -43,64 +99,80 @@ fn read_tools(idx: usize) -> Vec<(usize, usize)> {
     data
 }
 
+// Percorre as posições de inputs/tools_<idx> em ordem, colocando cada ferramenta
+// na próxima célula de grama que ainda está vazia. Se as posições acabam antes das
+// quantidades de `items_quantity`, avisa e coloca só as que couberam.
+pub fn place_tools<F>(idx: usize, items_quantity: &[(ToolType, usize)], terrain: F) -> Vec<Tool>
+where
+    F: Fn(usize, usize) -> Terrain,
+{
+    let mut tools: Vec<Tool> = vec![];
+    let mut idx_position: usize = 0;
+    let tools_positions = read_tools(idx);
+
+    for (tool, quantity) in items_quantity {
+        let mut cont = 0;
+        while cont < *quantity && idx_position < tools_positions.len() {
+            let (x, y) = tools_positions[idx_position];
+            idx_position += 1;
+            if terrain(x, y) == Terrain::Grass && !tools.iter().any(|t| t.x == x && t.y == y) {
+                tools.push(Tool::new(x, y, Some(*tool)));
+                cont += 1;
+            }
+        }
+        if cont < *quantity {
+            println!(
+                "Scenario {}: only {} of {} {} placed",
+                idx,
+                cont,
+                quantity,
+                tool_name(*tool)
+            );
+        }
+    }
+    tools
+}
+
 pub fn spawn_tools(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     board: Res<Board>,
-    windows: Res<Windows>,
+    layout: Res<GridLayout>,
     mut query: Query<&mut Cell>,
     params: Res<Params>,
 ) {
     asset_server.watch_for_changes().unwrap();
 
-    let window = windows.primary();
-    let border_width = 2.0;
-    let cell_width =
-        (window.width() - border_width * (board.width - 1) as f32) / (board.width as f32);
-    let cell_height =
-        (window.height() - border_width * (board.height - 1) as f32) / (board.height as f32);
-
-    let mut idx: usize = 0;
-    let tools_positions = read_tools(params.input_idx);
-
-    for (tool, quantity) in &params.items_quantity {
-        let mut cont = 0;
-        while cont < *quantity {
-            let (x, y) = tools_positions[idx];
-            idx += 1;
-            let mut cell = query.get_mut(board.cells[x][y]).unwrap();
-            match cell.terrain {
-                Terrain::Grass => match cell.tool {
-                    Some(_) => {}
-                    None => {
-                        let xx = x as f32;
-                        let yy = y as f32;
-                        let cx = -window.height() / 2.
-                            + cell_height * xx
-                            + border_width * xx
-                            + cell_height / 2.;
-                        let cy = -window.width() / 2.
-                            + cell_width * yy
-                            + border_width * yy
-                            + cell_width / 2.;
-                        commands
-                            .spawn_bundle(SpriteBundle {
-                                texture: asset_server.load("empty_texture.png"),
-                                transform: Transform::from_xyz(cx, cy, 2.0),
-                                sprite: Sprite {
-                                    custom_size: Some(Vec2::new(cell_width, cell_height)),
-                                    ..default()
-                                },
-                                ..default()
-                            })
-                            .insert(Tool::new(x, y, Some(*tool)));
-                        cell.tool = Some(*tool);
-                        cont += 1;
-                    }
+    let tools = place_tools(params.input_idx, &params.items_quantity, |x, y| {
+        query.get(board.cells[x][y]).unwrap().terrain
+    });
+    for tool in tools {
+        let (x, y) = (tool.x, tool.y);
+        let center = layout.center(GridCoord::new(x, y));
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: asset_server.load("empty_texture.png"),
+                transform: Transform::from_translation(center.extend(2.0)),
+                sprite: Sprite {
+                    custom_size: Some(layout.cell_size()),
+                    ..default()
                 },
-                _ => {}
-            }
-        }
+                ..default()
+            })
+            .insert(tool);
+        let mut cell = query.get_mut(board.cells[x][y]).unwrap();
+        cell.tool = tool.tool_type;
+    }
+}
+
+pub fn tool_texture(tool_type: Option<ToolType>) -> &'static str {
+    match tool_type {
+        Some(ToolType::Battery) => "battery.png",
+        Some(ToolType::WeldingArm) => "welding.png",
+        Some(ToolType::SuctionPump) => "pump.png",
+        Some(ToolType::CoolingDevice) => "cooling.png",
+        Some(ToolType::PneumaticArm) => "pneumatic.png",
+        None => "empty_texture.png",
     }
 }
 
@@ -109,13 +181,6 @@ pub fn render_tools(
     mut query: Query<(&Tool, &mut Handle<Image>), Changed<Tool>>,
 ) {
     for (tool, mut image_handle) in query.iter_mut() {
-        match tool.tool_type {
-            Some(ToolType::Battery) => *image_handle = asset_server.load("battery.png"),
-            Some(ToolType::WeldingArm) => *image_handle = asset_server.load("welding.png"),
-            Some(ToolType::SuctionPump) => *image_handle = asset_server.load("pump.png"),
-            Some(ToolType::CoolingDevice) => *image_handle = asset_server.load("cooling.png"),
-            Some(ToolType::PneumaticArm) => *image_handle = asset_server.load("pneumatic.png"),
-            None => *image_handle = asset_server.load("empty_texture.png"),
-        }
+        *image_handle = asset_server.load(tool_texture(tool.tool_type));
     }
 }
@@ -0,0 +1,274 @@
+use crate::movement::{Connectivity, Movement};
+use crate::terrain::{parse_terrain, Terrain};
+use crate::tool::{parse_tool, tool_name, ToolType};
+use std::fs;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TrajectoryEvent {
+    // Busca feita de (x, y) até o destino, com o custo encontrado.
+    Plan {
+        from: (usize, usize),
+        to: (usize, usize),
+        cost: Option<usize>,
+    },
+    Move {
+        x: usize,
+        y: usize,
+        cost: usize,
+        random: bool,
+    },
+    Pickup {
+        x: usize,
+        y: usize,
+        tool: ToolType,
+    },
+    Drop {
+        x: usize,
+        y: usize,
+        tool: ToolType,
+        quantity: usize,
+    },
+    // Requisição da fábrica em (x, y) encerrada.
+    Deliver {
+        x: usize,
+        y: usize,
+    },
+    Terrain {
+        x: usize,
+        y: usize,
+        terrain: Terrain,
+    },
+    Tool {
+        x: usize,
+        y: usize,
+        tool: ToolType,
+    },
+}
+
+// Registro de tudo que o agente fez, uma linha por evento, no mesmo estilo dos
+// arquivos de inputs/. O cabeçalho guarda o cenário, a semente e o modelo de
+// movimento, então o arquivo basta para repetir ou reproduzir a execução.
+#[derive(Default)]
+pub struct TrajectoryLog {
+    pub path: Option<String>,
+    pub input_idx: usize,
+    pub seed: u64,
+    pub movement: Movement,
+    pub events: Vec<TrajectoryEvent>,
+    pub cursor: usize,
+    // O que foi gravado ou reproduzido desde a última `take_pending`; é daqui que
+    // `publish_events` tira os eventos para quem está assinando.
+    pub pending: Vec<TrajectoryEvent>,
+}
+
+fn terrain_name(terrain: Terrain) -> &'static str {
+    match terrain {
+        Terrain::Grass => "grass",
+        Terrain::Mountain => "mountain",
+        Terrain::Swamp => "swamp",
+        Terrain::Desert => "desert",
+        Terrain::Obstacle => "obstacle",
+    }
+}
+
+impl TrajectoryLog {
+    pub fn new(path: String) -> Self {
+        Self {
+            path: Some(path),
+            ..Self::default()
+        }
+    }
+
+    pub fn push(&mut self, event: TrajectoryEvent) {
+        self.events.push(event);
+        self.pending.push(event);
+    }
+
+    pub fn take_pending(&mut self) -> Vec<TrajectoryEvent> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn to_text(&self) -> String {
+        let connectivity = match self.movement.connectivity {
+            Connectivity::Four => "four",
+            Connectivity::Eight => "eight",
+        };
+        let mut text = format!(
+            "input {}\nseed {}\nmovement {} {} {}\n",
+            self.input_idx,
+            self.seed,
+            connectivity,
+            self.movement.diagonal_cost,
+            self.movement.corner_cutting
+        );
+        for event in &self.events {
+            let line = match *event {
+                TrajectoryEvent::Plan { from, to, cost } => {
+                    let cost = match cost {
+                        Some(cost) => cost.to_string(),
+                        None => "-".to_string(),
+                    };
+                    format!("plan {} {} {} {} {}", from.0, from.1, to.0, to.1, cost)
+                }
+                TrajectoryEvent::Move { x, y, cost, random } => {
+                    let kind = if random { "random" } else { "move" };
+                    format!("{} {} {} {}", kind, x, y, cost)
+                }
+                TrajectoryEvent::Pickup { x, y, tool } => {
+                    format!("pickup {} {} {}", x, y, tool_name(tool))
+                }
+                TrajectoryEvent::Drop {
+                    x,
+                    y,
+                    tool,
+                    quantity,
+                } => format!("drop {} {} {} {}", x, y, tool_name(tool), quantity),
+                TrajectoryEvent::Deliver { x, y } => format!("deliver {} {}", x, y),
+                TrajectoryEvent::Terrain { x, y, terrain } => {
+                    format!("terrain {} {} {}", x, y, terrain_name(terrain))
+                }
+                TrajectoryEvent::Tool { x, y, tool } => {
+                    format!("tool {} {} {}", x, y, tool_name(tool))
+                }
+            };
+            text += &line;
+            text += "\n";
+        }
+        text
+    }
+
+    // Um registro com linha inválida não serve para reproduzir a execução, então a
+    // leitura para na primeira e diz qual é, em vez de trocar por um valor padrão.
+    // Registros sem a linha `movement` são de antes dela e usam o movimento padrão.
+    pub fn from_text(contents: &str) -> Result<Self, String> {
+        let mut log = Self::default();
+        for line in contents.split('\n') {
+            let values: Vec<&str> = line.split_whitespace().collect();
+            if values.is_empty() {
+                continue;
+            }
+            let fields = match values[0] {
+                "input" | "seed" => 2,
+                "deliver" => 3,
+                "move" | "random" | "pickup" | "terrain" | "tool" | "movement" => 4,
+                "drop" => 5,
+                "plan" => 6,
+                _ => return Err(format!("Unknown trajectory line: {}", line)),
+            };
+            if values.len() != fields {
+                return Err(format!(
+                    "Expected {} fields in trajectory line: {}",
+                    fields, line
+                ));
+            }
+            let invalid = || format!("Invalid value in trajectory line: {}", line);
+            let number = |i: usize| values[i].parse::<usize>().map_err(|_| invalid());
+            let tool = |i: usize| {
+                parse_tool(values[i])
+                    .ok_or_else(|| format!("Unknown tool in trajectory line: {}", line))
+            };
+            let event = match values[0] {
+                "input" => {
+                    log.input_idx = number(1)?;
+                    continue;
+                }
+                "seed" => {
+                    log.seed = values[1].parse().map_err(|_| invalid())?;
+                    continue;
+                }
+                "movement" => {
+                    let connectivity = match values[1] {
+                        "four" => Connectivity::Four,
+                        "eight" => Connectivity::Eight,
+                        _ => return Err(invalid()),
+                    };
+                    log.movement = Movement::new(
+                        connectivity,
+                        values[2].parse().map_err(|_| invalid())?,
+                        values[3].parse().map_err(|_| invalid())?,
+                    );
+                    continue;
+                }
+                "plan" => TrajectoryEvent::Plan {
+                    from: (number(1)?, number(2)?),
+                    to: (number(3)?, number(4)?),
+                    cost: match values[5] {
+                        "-" => None,
+                        _ => Some(number(5)?),
+                    },
+                },
+                "move" | "random" => TrajectoryEvent::Move {
+                    x: number(1)?,
+                    y: number(2)?,
+                    cost: number(3)?,
+                    random: values[0] == "random",
+                },
+                "pickup" => TrajectoryEvent::Pickup {
+                    x: number(1)?,
+                    y: number(2)?,
+                    tool: tool(3)?,
+                },
+                "drop" => TrajectoryEvent::Drop {
+                    x: number(1)?,
+                    y: number(2)?,
+                    tool: tool(3)?,
+                    quantity: number(4)?,
+                },
+                "deliver" => TrajectoryEvent::Deliver {
+                    x: number(1)?,
+                    y: number(2)?,
+                },
+                "terrain" => TrajectoryEvent::Terrain {
+                    x: number(1)?,
+                    y: number(2)?,
+                    terrain: parse_terrain(values[3])
+                        .ok_or_else(|| format!("Unknown terrain in trajectory line: {}", line))?,
+                },
+                _ => TrajectoryEvent::Tool {
+                    x: number(1)?,
+                    y: number(2)?,
+                    tool: tool(3)?,
+                },
+            };
+            log.events.push(event);
+        }
+        Ok(log)
+    }
+
+    // Um registro carregado só é reproduzido, então fica sem `path` e `save` não
+    // escreve por cima dele.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+        Self::from_text(&contents)
+    }
+
+    pub fn save(&self) {
+        if let Some(path) = &self.path {
+            fs::write(path, self.to_text()).expect("Something went wrong");
+            println!("Trajectory saved to {}", path);
+        }
+    }
+
+    // Eventos do próximo tick da reprodução: tudo até o próximo movimento,
+    // incluindo o que acontece logo depois dele (coletas, entregas).
+    pub fn next_tick(&mut self) -> Vec<TrajectoryEvent> {
+        let mut events = vec![];
+        let mut moved = false;
+        while self.cursor < self.events.len() {
+            let event = self.events[self.cursor];
+            let is_move = matches!(event, TrajectoryEvent::Move { .. });
+            if is_move && moved {
+                break;
+            }
+            moved |= is_move;
+            events.push(event);
+            self.pending.push(event);
+            self.cursor += 1;
+        }
+        events
+    }
+
+    pub fn finished(&self) -> bool {
+        self.cursor >= self.events.len()
+    }
+}
@@ -0,0 +1,126 @@
+use crate::agent::Agent;
+use crate::board::Board;
+use crate::cell::Cell;
+use crate::factory::Factory;
+use crate::grid::{GridCoord, GridLayout};
+use crate::tool::Tool;
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+
+fn place(
+    layout: &GridLayout,
+    (x, y): (usize, usize),
+    transform: &mut Transform,
+    sprite: &mut Sprite,
+) {
+    let center = layout.center(GridCoord::new(x, y));
+    transform.translation.x = center.x;
+    transform.translation.y = center.y;
+    sprite.custom_size = Some(layout.cell_size());
+}
+
+// Quando a janela muda de tamanho, refaz o `GridLayout` e reposiciona células,
+// ferramentas, fábricas e o agente.
+#[allow(clippy::type_complexity)]
+pub fn resize_layout(
+    mut resized: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    board: Res<Board>,
+    mut layout: ResMut<GridLayout>,
+    mut query_cell: Query<(&mut Transform, &mut Sprite), With<Cell>>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut Sprite,
+            Option<&Tool>,
+            Option<&Factory>,
+            Option<&Agent>,
+        ),
+        Without<Cell>,
+    >,
+) {
+    let primary = windows.primary().id();
+    let (width, height) = match resized.iter().filter(|event| event.id == primary).last() {
+        Some(event) => (event.width, event.height),
+        None => return,
+    };
+    *layout = GridLayout::new(width, height, &board);
+
+    for (x, row) in board.cells.iter().enumerate() {
+        for (y, entity) in row.iter().enumerate() {
+            if let Ok((mut transform, mut sprite)) = query_cell.get_mut(*entity) {
+                place(&layout, (x, y), &mut transform, &mut sprite);
+            }
+        }
+    }
+    for (mut transform, mut sprite, tool, factory, agent) in query.iter_mut() {
+        let position = match (tool, factory, agent) {
+            (Some(tool), _, _) => (tool.x, tool.y),
+            (_, Some(factory), _) => (factory.x, factory.y),
+            (_, _, Some(agent)) => agent.position(),
+            _ => continue,
+        };
+        place(&layout, position, &mut transform, &mut sprite);
+    }
+}
+
+#[derive(Component)]
+pub struct MainCamera;
+
+// A câmera do mundo, que o zoom e o arrasto movem, e a da interface, que desenha
+// o HUD e os painéis.
+pub fn setup_camera(mut commands: Commands) {
+    commands
+        .spawn_bundle(OrthographicCameraBundle::new_2d())
+        .insert(MainCamera);
+    commands.spawn_bundle(UiCameraBundle::default());
+}
+
+// Zoom com a roda do mouse, arrastar com o botão direito ou W A S D para mover a
+// câmera e Home para voltar à visão inicial.
+pub fn control_camera(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut motion: EventReader<MouseMotion>,
+    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let (mut transform, mut projection) = match query.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    for event in wheel.iter() {
+        let factor = if event.y > 0.0 { 0.9 } else { 1.1 };
+        projection.scale = (projection.scale * factor).clamp(0.1, 4.0);
+    }
+
+    let mut pan = Vec2::ZERO;
+    for event in motion.iter() {
+        if buttons.pressed(MouseButton::Right) {
+            pan += Vec2::new(-event.delta.x, event.delta.y);
+        }
+    }
+    let speed = 400.0 * time.delta_seconds();
+    if keys.pressed(KeyCode::W) {
+        pan.y += speed;
+    }
+    if keys.pressed(KeyCode::S) {
+        pan.y -= speed;
+    }
+    if keys.pressed(KeyCode::A) {
+        pan.x -= speed;
+    }
+    if keys.pressed(KeyCode::D) {
+        pan.x += speed;
+    }
+    transform.translation += (pan * projection.scale).extend(0.0);
+
+    if keys.just_pressed(KeyCode::Home) {
+        projection.scale = 1.0;
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+    }
+}
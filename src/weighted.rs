@@ -0,0 +1,83 @@
+use crate::movement::Movement;
+use crate::search::{print_distance, SearchStats};
+use crate::terrain::Terrain;
+use priority_queue::PriorityQueue;
+use std::collections::HashMap;
+
+// A* ponderado: f = g + epsilon * h. Com heurística consistente e sem reabrir nós
+// fechados o custo encontrado fica no máximo epsilon vezes o ótimo.
+#[allow(clippy::too_many_arguments)]
+pub fn weighted_a_star<F>(
+    (ax, ay): (i32, i32),
+    (dx, dy): (i32, i32),
+    width: i32,
+    height: i32,
+    terrain: F,
+    movement: &Movement,
+    epsilon: f32,
+    stats: &mut SearchStats,
+) -> Option<Vec<(i32, i32)>>
+where
+    F: Fn(i32, i32) -> Terrain,
+{
+    let start = (ax, ay);
+    let goal = (dx, dy);
+    // A prioridade é guardada em milésimos para continuar sendo inteira.
+    let weight = (epsilon.max(1.0) as f64 * 1000.0).round() as i64;
+    let priority = |g: i32, node: (i32, i32)| {
+        -(g as i64 * 1000 + weight * movement.heuristic(node, goal) as i64)
+    };
+    let mut pq = PriorityQueue::new();
+    let mut distance: HashMap<(i32, i32), i32> = HashMap::new();
+    let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut closed: HashMap<(i32, i32), bool> = HashMap::new();
+
+    println!(
+        "\nWeighted search (epsilon {}) from ({}, {}) to ({}, {})",
+        epsilon, ax, ay, dx, dy
+    );
+    distance.insert(start, 0);
+    pq.push(start, priority(0, start));
+    while let Some((current, _)) = pq.pop() {
+        if current == goal {
+            break;
+        }
+        closed.insert(current, true);
+        stats.expand(pq.len() + 1);
+        stats.close(current);
+        for &(mx, my) in movement.offsets() {
+            if !movement.can_move(current, (mx, my), width, height, &terrain) {
+                continue;
+            }
+            let next = (current.0 + mx, current.1 + my);
+            if closed.contains_key(&next) {
+                continue;
+            }
+            let n_distance =
+                distance[&current] + movement.step_cost((mx, my), terrain(next.0, next.1)) as i32;
+            if distance.contains_key(&next) && n_distance >= distance[&next] {
+                continue;
+            }
+            distance.insert(next, n_distance);
+            parent.insert(next, current);
+            pq.push(next, priority(n_distance, next));
+            stats.opened.push(next);
+        }
+    }
+
+    if !distance.contains_key(&goal) || start == goal {
+        print_distance(start, goal, None, movement, &terrain);
+        return None;
+    }
+
+    let mut moves: Vec<(i32, i32)> = vec![];
+    let mut current = goal;
+    while let Some(previous) = parent.get(&current) {
+        moves.push((current.0 - previous.0, current.1 - previous.1));
+        current = *previous;
+    }
+    moves.reverse();
+    print_distance(start, goal, Some(&moves), movement, &terrain);
+    println!("Path: {:?}", moves);
+    Some(moves)
+}
@@ -0,0 +1,227 @@
+use crate::board::Board;
+use crate::cell::Cell;
+use crate::grid::{GridCoord, GridLayout};
+use crate::params::Params;
+use crate::rng::SimRng;
+use crate::terrain::{parse_terrain, Terrain};
+use crate::tool::*;
+use crate::trajectory::{TrajectoryEvent, TrajectoryLog};
+use bevy::prelude::*;
+use rand::Rng;
+use std::fs;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WorldEventKind {
+    Terrain(Terrain),
+    Block(usize),
+    DropTool(ToolType),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct WorldEvent {
+    pub tick: usize,
+    pub x: usize,
+    pub y: usize,
+    pub kind: WorldEventKind,
+}
+
+impl WorldEvent {
+    pub fn new(tick: usize, x: usize, y: usize, kind: WorldEventKind) -> Self {
+        Self { tick, x, y, kind }
+    }
+}
+
+#[derive(Default)]
+pub struct WorldEvents {
+    pub tick: usize,
+    pub pending: Vec<WorldEvent>,
+    // (até o tick, x, y, terreno que volta quando o bloqueio acaba)
+    pub restore: Vec<(usize, usize, usize, Terrain)>,
+}
+
+impl WorldEvents {
+    // Tira da lista os bloqueios que acabam em `tick` e devolve o terreno que
+    // volta para cada célula.
+    pub fn unblock(&mut self, tick: usize) -> Vec<(usize, usize, Terrain)> {
+        let mut unblocked = vec![];
+        let mut restore = vec![];
+        for (until, x, y, terrain) in self.restore.drain(..) {
+            if until <= tick {
+                unblocked.push((x, y, terrain));
+            } else {
+                restore.push((until, x, y, terrain));
+            }
+        }
+        self.restore = restore;
+        unblocked
+    }
+
+    // Bloqueia (x, y), que tem o terreno `current`, até `until`. Um bloqueio em
+    // cima de outro só estende o prazo e mantém o terreno guardado.
+    pub fn block(&mut self, until: usize, x: usize, y: usize, current: Terrain) {
+        match self.restore.iter_mut().find(|r| r.1 == x && r.2 == y) {
+            Some(blocked) => blocked.0 = blocked.0.max(until),
+            None => self.restore.push((until, x, y, current)),
+        }
+    }
+
+    // Muda o terreno de (x, y) e devolve o que vale agora, ou `None` se a célula
+    // está bloqueada: aí a mudança fica guardada para quando o bloqueio acabar.
+    pub fn change_terrain(&mut self, x: usize, y: usize, terrain: Terrain) -> Option<Terrain> {
+        match self.restore.iter_mut().find(|r| r.1 == x && r.2 == y) {
+            Some(blocked) => {
+                blocked.3 = terrain;
+                None
+            }
+            None => Some(terrain),
+        }
+    }
+}
+
+// Cada linha de inputs/events_<idx> descreve um evento:
+//   <tick> terrain <x> <y> <terreno>
+//   <tick> block <x> <y> <duração em ticks>
+//   <tick> tool <x> <y> <ferramenta>
+fn read_events(idx: usize) -> Vec<WorldEvent> {
+    let mut data: Vec<WorldEvent> = Vec::<WorldEvent>::default();
+    let field_path = "inputs/events_".to_string() + &idx.to_string();
+    let contents = match fs::read_to_string(field_path) {
+        Ok(contents) => contents,
+        Err(_) => return data,
+    };
+    for line in contents.split('\n') {
+        let values: Vec<&str> = line.split_whitespace().collect();
+        if values.len() != 5 {
+            continue;
+        }
+        let tick = values[0].parse::<usize>().unwrap();
+        let x = values[2].parse::<usize>().unwrap();
+        let y = values[3].parse::<usize>().unwrap();
+        let kind = match values[1] {
+            "terrain" => parse_terrain(values[4]).map(WorldEventKind::Terrain),
+            "block" => values[4].parse::<usize>().ok().map(WorldEventKind::Block),
+            "tool" => parse_tool(values[4]).map(WorldEventKind::DropTool),
+            _ => None,
+        };
+        match kind {
+            Some(kind) => data.push(WorldEvent::new(tick, x, y, kind)),
+            None => println!("Ignoring invalid event: {}", line),
+        }
+    }
+    data
+}
+
+pub fn setup_world_events(mut events: ResMut<WorldEvents>, params: Res<Params>) {
+    events.pending = read_events(params.input_idx);
+}
+
+fn random_event(tick: usize, board: &Board, rng: &mut SimRng) -> WorldEvent {
+    let x = rng.gen_range(0..board.width);
+    let y = rng.gen_range(0..board.height);
+    let kind = match rng.gen_range(0..3) {
+        0 => WorldEventKind::Terrain(Terrain::Swamp),
+        1 => WorldEventKind::Terrain(Terrain::Desert),
+        _ => WorldEventKind::Block(rng.gen_range(5..50)),
+    };
+    WorldEvent::new(tick, x, y, kind)
+}
+
+// Sprite de uma ferramenta que apareceu no meio da execução. Usado pelos eventos
+// e pela reprodução de uma trajetória.
+pub fn spawn_tool(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    layout: &GridLayout,
+    (x, y): (usize, usize),
+    tool: ToolType,
+) {
+    let center = layout.center(GridCoord::new(x, y));
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: asset_server.load("empty_texture.png"),
+            transform: Transform::from_translation(center.extend(2.0)),
+            sprite: Sprite {
+                custom_size: Some(layout.cell_size()),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Tool::new(x, y, Some(tool)));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn apply_world_events(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    layout: Res<GridLayout>,
+    board: Res<Board>,
+    params: Res<Params>,
+    mut events: ResMut<WorldEvents>,
+    mut rng: ResMut<SimRng>,
+    mut log: ResMut<TrajectoryLog>,
+    mut query_cell: Query<&mut Cell>,
+) {
+    events.tick += 1;
+    let tick = events.tick;
+
+    for (x, y, terrain) in events.unblock(tick) {
+        let mut cell = query_cell.get_mut(board.cells[x][y]).unwrap();
+        println!("\nEvent: ({} {}) is no longer blocked", x, y);
+        cell.terrain = terrain;
+        log.push(TrajectoryEvent::Terrain { x, y, terrain });
+    }
+
+    let mut due: Vec<WorldEvent> = vec![];
+    let mut pending: Vec<WorldEvent> = vec![];
+    for event in events.pending.drain(..) {
+        if event.tick <= tick {
+            due.push(event);
+        } else {
+            pending.push(event);
+        }
+    }
+    events.pending = pending;
+    if params.event_rate > 0.0 && rng.gen_bool(params.event_rate) {
+        due.push(random_event(tick, &board, &mut rng));
+    }
+
+    for event in due {
+        let (x, y) = (event.x, event.y);
+        if x >= board.width || y >= board.height {
+            continue;
+        }
+        let mut cell = query_cell.get_mut(board.cells[x][y]).unwrap();
+        match event.kind {
+            WorldEventKind::Terrain(terrain) => match events.change_terrain(x, y, terrain) {
+                Some(terrain) => {
+                    println!("\nEvent: terrain at ({} {}) is now {:?}", x, y, terrain);
+                    cell.terrain = terrain;
+                    log.push(TrajectoryEvent::Terrain { x, y, terrain });
+                }
+                None => println!(
+                    "\nEvent: terrain at ({} {}) will be {:?} once unblocked",
+                    x, y, terrain
+                ),
+            },
+            WorldEventKind::Block(ticks) => {
+                println!("\nEvent: ({} {}) blocked for {} ticks", x, y, ticks);
+                events.block(tick + ticks, x, y, cell.terrain);
+                cell.terrain = Terrain::Obstacle;
+                log.push(TrajectoryEvent::Terrain {
+                    x,
+                    y,
+                    terrain: Terrain::Obstacle,
+                });
+            }
+            WorldEventKind::DropTool(tool) => {
+                if cell.tool.is_some() || cell.factory.is_some() || !cell.terrain.passable() {
+                    continue;
+                }
+                println!("\nEvent: {:?} dropped at ({} {})", tool, x, y);
+                spawn_tool(&mut commands, &asset_server, &layout, (x, y), tool);
+                cell.tool = Some(tool);
+                log.push(TrajectoryEvent::Tool { x, y, tool });
+            }
+        }
+    }
+}
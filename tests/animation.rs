@@ -0,0 +1,46 @@
+use robozinho::animation::*;
+use robozinho::board::Board;
+use robozinho::grid::{GridCoord, GridLayout};
+use robozinho::terrain::Terrain;
+
+#[test]
+fn step_duration_scales_with_terrain_cost() {
+    let animation = Animation::default();
+    let grass = animation.duration(Terrain::Grass);
+    assert!(grass > 0.0);
+    assert_eq!(animation.duration(Terrain::Desert), grass * 20.0);
+    assert!(animation.duration(Terrain::Swamp) > animation.duration(Terrain::Mountain));
+
+    let off = Animation {
+        enabled: false,
+        ..Animation::default()
+    };
+    assert_eq!(off.duration(Terrain::Desert), 0.0);
+}
+
+#[test]
+fn motion_interpolates_between_cell_centers() {
+    let layout = GridLayout::new(100.0, 100.0, &Board::new(10, 10));
+    let mut motion = Motion::at((2, 3));
+    assert!(motion.finished());
+    assert_eq!(
+        motion.position(&layout),
+        layout.center(GridCoord::new(2, 3))
+    );
+
+    motion.start((2, 3), (3, 3), 0.4);
+    assert!(!motion.finished());
+    assert_eq!(
+        motion.position(&layout),
+        layout.center(GridCoord::new(2, 3))
+    );
+
+    motion.elapsed = 0.2;
+    let from = layout.center(GridCoord::new(2, 3));
+    let to = layout.center(GridCoord::new(3, 3));
+    assert!((motion.position(&layout) - (from + to) / 2.0).length() < 1e-4);
+
+    motion.elapsed = 1.0;
+    assert!(motion.finished());
+    assert_eq!(motion.position(&layout), to);
+}
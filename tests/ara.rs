@@ -0,0 +1,56 @@
+use robozinho::ara::{AraStar, Budget};
+use robozinho::board::*;
+use robozinho::dstar::DStarLite;
+use robozinho::movement::Movement;
+use robozinho::search::SearchStats;
+
+mod common;
+use common::*;
+
+#[test]
+fn anytime_solutions_improve_down_to_the_optimum() {
+    let board = Board::new(42, 42);
+    let terrain = board_terrain(&board);
+    let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+    let movement = Movement::default();
+
+    for idx in 1..=5 {
+        let start = read_positions(&format!("inputs/agent_{}", idx))[0];
+        let mut goals = read_positions(&format!("inputs/factories_{}", idx));
+        goals.truncate(5);
+        for goal in goals {
+            if goal == start {
+                continue;
+            }
+            let mut stats = SearchStats::default();
+            let mut dstar = DStarLite::new(start, goal, 42, 42, movement);
+            let moves = dstar.replan(start, lookup, &mut stats).unwrap();
+            let optimal = path_cost(start, goal, &moves, &terrain, &movement);
+
+            let mut ara = AraStar::new(start, goal, 42, 42, movement, 3.0, 0.5);
+            let mut ticks = 0;
+            while !ara.improve(lookup, Budget::Expansions(50), &mut stats) {
+                ticks += 1;
+                assert!(ticks < 1000);
+            }
+            let first = ara.path_from(start).unwrap();
+            let first_cost = path_cost(start, goal, &first, &terrain, &movement);
+            assert!(first_cost as f64 <= optimal as f64 * 3.0);
+
+            // Depois de andar alguns passos o caminho é refeito a partir da nova posição.
+            let (mut x, mut y) = start;
+            for (mx, my) in first.iter().take(first.len() / 2) {
+                x += mx;
+                y += my;
+            }
+            while !ara.finished() {
+                ara.improve(lookup, Budget::Expansions(50), &mut stats);
+            }
+            assert_eq!(ara.epsilon(), 1.0);
+            let last = ara.path_from(start).unwrap();
+            assert_eq!(path_cost(start, goal, &last, &terrain, &movement), optimal);
+            let rest = ara.path_from((x, y)).unwrap();
+            path_cost((x, y), goal, &rest, &terrain, &movement);
+        }
+    }
+}
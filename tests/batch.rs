@@ -0,0 +1,48 @@
+use robozinho::batch::*;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn seeds_depend_only_on_scenario_and_repetition() {
+    let specs = plan(&[1, 2], &STRATEGIES[..3], 2, 42);
+    assert_eq!(specs.len(), 12);
+    for spec in &specs {
+        assert_eq!(spec.seed, run_seed(42, spec.scenario, spec.repetition));
+    }
+    // Estratégias do mesmo cenário e repetição dividem a semente; o resto não.
+    let seeds: HashSet<u64> = specs.iter().map(|spec| spec.seed).collect();
+    assert_eq!(seeds.len(), 4);
+    assert_ne!(run_seed(42, 1, 0), run_seed(43, 1, 0));
+}
+
+#[test]
+fn results_do_not_depend_on_thread_count() {
+    let specs = plan(&[1, 3], &STRATEGIES, 2, 7);
+    let calls = AtomicUsize::new(0);
+    let serial = run_batch(&specs, 42, 42, 1, |done, total| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        assert!(done <= total);
+    });
+    let parallel = run_batch(&specs, 42, 42, 4, |_, _| {});
+    assert_eq!(calls.load(Ordering::SeqCst), specs.len());
+
+    assert_eq!(serial.len(), specs.len());
+    for ((serial, parallel), spec) in serial.iter().zip(&parallel).zip(&specs) {
+        assert_eq!(serial.spec, *spec);
+        assert_eq!(parallel.spec, *spec);
+        assert_eq!(serial.summary, parallel.summary);
+        assert!(serial.summary.finished);
+    }
+}
+
+#[test]
+fn csv_has_one_row_per_run() {
+    let specs = plan(&[2], &STRATEGIES[..2], 1, 1);
+    let results = run_batch(&specs, 42, 42, 2, |_, _| {});
+    let csv = to_csv(&results);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], CSV_HEADER);
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with(&format!("2,estrela,0,{},true,", specs[0].seed)));
+    assert!(lines[2].starts_with(&format!("2,guloso,0,{},true,", specs[1].seed)));
+}
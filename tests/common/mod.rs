@@ -0,0 +1,45 @@
+// Funções de apoio dos testes de planejadores. Nem todo teste usa todas.
+#![allow(dead_code)]
+
+use robozinho::movement::Movement;
+use robozinho::terrain::Terrain;
+use std::fs;
+
+pub fn read_positions(path: &str) -> Vec<(i32, i32)> {
+    let contents = fs::read_to_string(path).expect("Something went wrong");
+    let mut data: Vec<(i32, i32)> = vec![];
+    for line in contents.split('\n') {
+        let values: Vec<&str> = line.split_whitespace().collect();
+        if values.len() != 2 {
+            break;
+        }
+        data.push((values[0].parse().unwrap(), values[1].parse().unwrap()));
+    }
+    data
+}
+
+// Custo de seguir `moves` a partir de `start`, conferindo cada passo e que o
+// caminho termina em `goal`.
+pub fn path_cost(
+    start: (i32, i32),
+    goal: (i32, i32),
+    moves: &[(i32, i32)],
+    terrain: &[Vec<Terrain>],
+    movement: &Movement,
+) -> usize {
+    let (width, height) = (terrain.len() as i32, terrain[0].len() as i32);
+    let (mut x, mut y) = start;
+    let mut cost = 0;
+    for &(mx, my) in moves {
+        assert!(
+            movement.can_move((x, y), (mx, my), width, height, |x: i32, y: i32| {
+                terrain[x as usize][y as usize]
+            })
+        );
+        x += mx;
+        y += my;
+        cost += movement.step_cost((mx, my), terrain[x as usize][y as usize]);
+    }
+    assert_eq!((x, y), goal);
+    cost
+}
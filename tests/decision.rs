@@ -0,0 +1,51 @@
+use robozinho::decision::*;
+use robozinho::factory::Factory;
+use robozinho::tool::ToolType;
+
+#[test]
+fn requisitions_are_queued_once_the_tools_are_held() {
+    let requisitions = vec![
+        Factory::new(1, 1, Some(ToolType::Battery), 2),
+        Factory::new(5, 5, Some(ToolType::SuctionPump), 1),
+        Factory::new(9, 9, None, 0),
+    ];
+    let mut state = empty_inventory();
+    let mut queue = vec![];
+    state[tool_index(ToolType::Battery)].1 = 2;
+    assert!(!check_requisitions(&state, &requisitions, &mut queue));
+    assert_eq!(queue, vec![(1, 1)]);
+    // Quem já está na fila não entra de novo.
+    assert!(!check_requisitions(&state, &requisitions, &mut queue));
+    assert_eq!(queue, vec![(1, 1)]);
+}
+
+#[test]
+fn radius_queues_only_requested_tools() {
+    let requisitions = vec![Factory::new(0, 0, Some(ToolType::WeldingArm), 1)];
+    let tool_at = |x: usize, y: usize| match (x, y) {
+        (2, 3) => Some(ToolType::WeldingArm),
+        (3, 3) => Some(ToolType::Battery),
+        (9, 9) => Some(ToolType::WeldingArm),
+        _ => None,
+    };
+    let mut queue = vec![];
+    check_radius((3, 3), 1, 10, 10, &requisitions, &mut queue, tool_at);
+    assert_eq!(queue, vec![(2, 3)]);
+}
+
+#[test]
+fn visiting_a_factory_closes_the_requisition() {
+    let factory = Factory::new(4, 2, Some(ToolType::CoolingDevice), 2);
+    let mut requisitions = vec![factory];
+    let mut queue = vec![(4, 2), (7, 7)];
+    let mut state = empty_inventory();
+    state[tool_index(ToolType::CoolingDevice)].1 = 3;
+
+    let delivered = visit_factory(&factory, &mut state, &mut requisitions, &mut queue);
+    assert_eq!(delivered, Some((ToolType::CoolingDevice, 2)));
+    assert_eq!(state[tool_index(ToolType::CoolingDevice)].1, 1);
+    assert_eq!(queue, vec![(7, 7)]);
+    assert_eq!(requisitions[0].needed_tool, None);
+    assert!(!should_find_path(None, Some(requisitions[0]), &requisitions));
+    assert!(should_find_path(None, Some(factory), &requisitions));
+}
@@ -0,0 +1,77 @@
+use robozinho::board::*;
+use robozinho::editor::*;
+use robozinho::factory::Factory;
+use robozinho::terrain::Terrain;
+use robozinho::tool::{parse_quantities, quantities_text, Tool, ToolType};
+
+#[test]
+fn field_text_reads_back_the_same_terrain() {
+    let mut terrain = vec![vec![Terrain::Grass; 3]; 5];
+    terrain[0][2] = Terrain::Obstacle;
+    terrain[4][0] = Terrain::Desert;
+    terrain[2][1] = Terrain::Swamp;
+    terrain[1][1] = Terrain::Mountain;
+    let text = field_text(&terrain);
+    assert_eq!(text.lines().next(), Some("4 0 0 0 0"));
+    assert_eq!(terrain_grid(&parse_field(&text), 5, 3), terrain);
+}
+
+#[test]
+fn tools_are_written_grouped_by_type() {
+    let editor = Editor {
+        tools: vec![
+            Tool::new(1, 1, Some(ToolType::SuctionPump)),
+            Tool::new(2, 2, Some(ToolType::Battery)),
+            Tool::new(3, 3, Some(ToolType::SuctionPump)),
+        ],
+        factories: vec![
+            Factory::new(4, 4, Some(ToolType::SuctionPump), 2),
+            Factory::new(5, 5, Some(ToolType::Battery), 1),
+        ],
+        agent: Some((6, 6)),
+        ..Editor::new()
+    };
+    assert_eq!(
+        editor.items_quantity(),
+        vec![(ToolType::Battery, 1), (ToolType::SuctionPump, 2)]
+    );
+    assert_eq!(editor.tools_text(), "2 2\n1 1\n3 3\n");
+    assert_eq!(
+        editor.factories_needs(),
+        vec![(ToolType::SuctionPump, 2), (ToolType::Battery, 1)]
+    );
+    assert_eq!(editor.factories_text(), "4 4\n5 5\n");
+    assert_eq!(editor.agent_text(), "6 6\n");
+
+    // As quantidades vão junto, para que o cenário volte com as mesmas requisições.
+    assert_eq!(
+        quantities_text(&editor.items_quantity()),
+        "battery 1\npump 2\n"
+    );
+    let needs = quantities_text(&editor.factories_needs());
+    assert_eq!(parse_quantities(&needs), editor.factories_needs());
+}
+
+#[test]
+fn placement_follows_the_loader_rules() {
+    let editor = Editor {
+        tools: vec![Tool::new(1, 1, Some(ToolType::Battery))],
+        agent: Some((2, 2)),
+        ..Editor::new()
+    };
+    assert!(!editor.can_paint(Brush::Tool, 0, 0, Terrain::Swamp));
+    assert!(editor.can_paint(Brush::Tool, 0, 0, Terrain::Grass));
+    assert!(!editor.can_paint(Brush::Factory, 1, 1, Terrain::Grass));
+    assert!(!editor.can_paint(Brush::Factory, 0, 0, Terrain::Obstacle));
+    assert!(!editor.can_paint(Brush::Terrain(Terrain::Mountain), 1, 1, Terrain::Grass));
+    assert!(!editor.can_paint(Brush::Terrain(Terrain::Obstacle), 2, 2, Terrain::Grass));
+    assert!(editor.can_paint(Brush::Terrain(Terrain::Mountain), 2, 2, Terrain::Grass));
+}
+
+#[test]
+fn shipped_scenarios_are_not_overwritten() {
+    let idx = Editor::save_index(1);
+    assert_ne!(idx, 1);
+    assert!(!std::path::Path::new(&format!("inputs/agent_{}", idx)).exists());
+    assert_eq!(Editor::save_index(idx), idx);
+}
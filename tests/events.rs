@@ -0,0 +1,97 @@
+use robozinho::events::*;
+use robozinho::terrain::Terrain;
+use robozinho::tool::ToolType;
+use robozinho::trajectory::*;
+use std::sync::{Arc, Mutex};
+
+fn sample_log() -> TrajectoryLog {
+    let mut log = TrajectoryLog::default();
+    log.push(TrajectoryEvent::Plan {
+        from: (0, 0),
+        to: (0, 1),
+        cost: Some(1),
+    });
+    log.push(TrajectoryEvent::Move {
+        x: 0,
+        y: 1,
+        cost: 1,
+        random: false,
+    });
+    log.push(TrajectoryEvent::Pickup {
+        x: 0,
+        y: 1,
+        tool: ToolType::Battery,
+    });
+    log.push(TrajectoryEvent::Terrain {
+        x: 3,
+        y: 3,
+        terrain: Terrain::Obstacle,
+    });
+    log.push(TrajectoryEvent::Move {
+        x: 1,
+        y: 1,
+        cost: 1,
+        random: false,
+    });
+    log.push(TrajectoryEvent::Drop {
+        x: 1,
+        y: 1,
+        tool: ToolType::Battery,
+        quantity: 1,
+    });
+    log.push(TrajectoryEvent::Deliver { x: 1, y: 1 });
+    log
+}
+
+#[test]
+fn pushed_events_are_pending_until_taken() {
+    let mut log = sample_log();
+    assert_eq!(log.take_pending().len(), 7);
+    assert!(log.take_pending().is_empty());
+}
+
+#[test]
+fn observers_see_agent_events_in_order() {
+    let mut log = sample_log();
+    log.take_pending();
+    let seen = Arc::new(Mutex::new(vec![]));
+    let mut observers = Observers::default();
+    let sink = seen.clone();
+    observers.subscribe(move |event| sink.lock().unwrap().push(*event));
+
+    replay_events(&mut log, &mut observers);
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 7);
+    assert_eq!(
+        seen[2],
+        SimEvent::ToolPicked(ToolPicked {
+            x: 0,
+            y: 1,
+            tool: ToolType::Battery
+        })
+    );
+    assert_eq!(
+        seen[4],
+        SimEvent::ToolDelivered(ToolDelivered {
+            x: 1,
+            y: 1,
+            tool: ToolType::Battery,
+            quantity: 1
+        })
+    );
+    assert_eq!(
+        seen[5],
+        SimEvent::FactoryCompleted(FactoryCompleted { x: 1, y: 1 })
+    );
+    assert_eq!(
+        seen[6],
+        SimEvent::RunFinished(RunFinished {
+            cost: 2,
+            expansions: 0,
+            random_moves: 0,
+            random_moves_cost: 0
+        })
+    );
+    assert!(log.take_pending().is_empty());
+}
@@ -0,0 +1,39 @@
+use robozinho::belief::Belief;
+use robozinho::exploration::frontier_step;
+use robozinho::movement::Movement;
+use robozinho::terrain::Terrain;
+
+// Crença `[x][y]` com as células de `known` conhecidas como grama e o resto
+// desconhecido.
+fn belief(width: usize, height: usize, known: impl Fn(usize, usize) -> bool) -> Belief {
+    let mut belief = Belief::new(width, height);
+    for x in 0..width {
+        for y in 0..height {
+            if known(x, y) {
+                belief.cells[x][y] = Some(Terrain::Grass);
+            }
+        }
+    }
+    belief
+}
+
+#[test]
+fn frontier_on_a_wide_map() {
+    // 8 de largura e 3 de altura: a fronteira fica em x = 5, além da altura.
+    let belief = belief(8, 3, |x, _| x <= 5);
+    let step = frontier_step(&belief, 0, 1, 1, &Movement::default());
+    assert_eq!(step, Some((1, 0)));
+}
+
+#[test]
+fn frontier_on_a_tall_map() {
+    let belief = belief(3, 8, |_, y| y <= 5);
+    let step = frontier_step(&belief, 1, 0, 1, &Movement::default());
+    assert_eq!(step, Some((0, 1)));
+}
+
+#[test]
+fn no_frontier_when_everything_is_known() {
+    let belief = belief(8, 3, |_, _| true);
+    assert_eq!(frontier_step(&belief, 0, 1, 1, &Movement::default()), None);
+}
@@ -0,0 +1,65 @@
+use robozinho::board::terrain_color;
+use robozinho::export::*;
+use robozinho::factory::Factory;
+use robozinho::terrain::Terrain;
+use robozinho::tool::{Tool, ToolType};
+use robozinho::trajectory::TrajectoryEvent;
+
+fn pixel(terrain: Terrain) -> [u8; 4] {
+    let (red, green, blue) = terrain_color(terrain);
+    [
+        (red * 255.).round() as u8,
+        (green * 255.).round() as u8,
+        (blue * 255.).round() as u8,
+        255,
+    ]
+}
+
+fn snapshot() -> Snapshot {
+    let mut terrain = vec![vec![Terrain::Grass; 4]; 3];
+    terrain[2][0] = Terrain::Desert;
+    Snapshot {
+        terrain,
+        tools: vec![Tool::new(1, 1, Some(ToolType::Battery))],
+        factories: vec![Factory::new(2, 3, Some(ToolType::Battery), 1)],
+        agent: (0, 0),
+    }
+}
+
+#[test]
+fn frames_follow_the_window_layout() {
+    let mut renderer = Renderer::new(8, 2);
+    let frame = renderer.draw(&snapshot());
+    // 3 células em x (largura) e 4 em y (altura), com 2 pixels entre elas.
+    assert_eq!(frame.dimensions(), (3 * 10 - 2, 4 * 10 - 2));
+    // y cresce para cima: (2, 0) é a célula do canto inferior direito.
+    assert_eq!(frame.get_pixel(20, 30).0, pixel(Terrain::Desert));
+    assert_eq!(frame.get_pixel(10, 10).0, pixel(Terrain::Grass));
+    assert_eq!(frame.get_pixel(9, 9).0, [102, 102, 102, 255]);
+}
+
+#[test]
+fn trajectory_events_update_the_snapshot() {
+    let mut snapshot = snapshot();
+    snapshot.apply(TrajectoryEvent::Move {
+        x: 1,
+        y: 1,
+        cost: 1,
+        random: false,
+    });
+    snapshot.apply(TrajectoryEvent::Pickup {
+        x: 1,
+        y: 1,
+        tool: ToolType::Battery,
+    });
+    snapshot.apply(TrajectoryEvent::Deliver { x: 2, y: 3 });
+    snapshot.apply(TrajectoryEvent::Terrain {
+        x: 0,
+        y: 3,
+        terrain: Terrain::Obstacle,
+    });
+    assert_eq!(snapshot.agent, (1, 1));
+    assert_eq!(snapshot.tools[0].tool_type, None);
+    assert_eq!(snapshot.factories[0].needed_tool, None);
+    assert_eq!(snapshot.terrain[0][3], Terrain::Obstacle);
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::Vec2;
+use robozinho::board::*;
+use robozinho::grid::{GridCoord, GridLayout};
+use robozinho::movement::Movement;
+use robozinho::search::{a_star, SearchStats};
+use robozinho::terrain::Terrain;
+
+// 5 colunas e 3 linhas: deserto no canto superior direito do arquivo, montanha no
+// inferior esquerdo e um pântano logo abaixo do deserto.
+const FIELD: &str = "0 0 0 0 3\n0 0 0 0 2\n1 0 0 0 0\n";
+
+fn grid() -> (Board, Vec<Vec<Terrain>>) {
+    let board = Board::new(5, 3);
+    let terrain = terrain_grid(&parse_field(FIELD), board.width, board.height);
+    (board, terrain)
+}
+
+#[test]
+fn terrain_is_indexed_by_column_then_row_from_the_bottom() {
+    let (_, terrain) = grid();
+    assert_eq!(terrain.len(), 5);
+    assert_eq!(terrain[0].len(), 3);
+    assert_eq!(terrain[4][2], Terrain::Desert);
+    assert_eq!(terrain[4][1], Terrain::Swamp);
+    assert_eq!(terrain[0][0], Terrain::Mountain);
+    assert_eq!(GridCoord::new(4, 2).field_index(3), (0, 4));
+}
+
+#[test]
+fn sprites_sit_on_the_cell_the_planner_sees() {
+    let (board, terrain) = grid();
+    let layout = GridLayout::new(500., 300., &board);
+
+    // O deserto aparece no canto superior direito da janela...
+    let desert = layout.center(GridCoord::new(4, 2));
+    assert!(desert.x > 200. && desert.y > 50.);
+    // ...e a montanha no inferior esquerdo.
+    let mountain = layout.center(GridCoord::new(0, 0));
+    assert!(mountain.x < -200. && mountain.y < -50.);
+
+    for x in 0..board.width {
+        for y in 0..board.height {
+            let coord = GridCoord::new(x, y);
+            assert_eq!(layout.to_grid(layout.center(coord)), Some(coord));
+        }
+    }
+    assert_eq!(layout.to_grid(Vec2::new(-260., 0.)), None);
+    assert_eq!(layout.to_grid(Vec2::new(0., 160.)), None);
+
+    // O planejador, com a mesma largura e altura, chega ao deserto pelo pântano.
+    let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+    let mut stats = SearchStats::default();
+    let moves = a_star(
+        (0, 2),
+        (4, 2),
+        board.width as i32,
+        board.height as i32,
+        lookup,
+        &Movement::default(),
+        1,
+        1,
+        &mut stats,
+    )
+    .unwrap();
+    let (mut x, mut y) = (0, 2);
+    for (mx, my) in moves {
+        x += mx;
+        y += my;
+        assert!(GridCoord::from_signed((x, y), board.width, board.height).is_some());
+    }
+    assert_eq!((x, y), (4, 2));
+}
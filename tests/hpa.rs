@@ -0,0 +1,102 @@
+use robozinho::board::*;
+use robozinho::hpa::Hpa;
+use robozinho::movement::Movement;
+use robozinho::search::{a_star, SearchStats};
+use robozinho::terrain::Terrain;
+
+mod common;
+use common::*;
+
+fn follow(start: (i32, i32), moves: &[(i32, i32)], terrain: &[Vec<Terrain>]) -> (i32, i32) {
+    let (mut x, mut y) = start;
+    for (mx, my) in moves {
+        assert_eq!(mx.abs() + my.abs(), 1);
+        x += mx;
+        y += my;
+        assert!(terrain[x as usize][y as usize].passable());
+    }
+    (x, y)
+}
+
+// Sempre que existe caminho o HPA* acha um, e o custo fica a no máximo 25% do
+// ótimo dado pelo Dijkstra (`a_star` sem heurística).
+#[test]
+fn hierarchical_paths_reach_the_goal_on_shipped_scenarios() {
+    let board = Board::new(42, 42);
+    let terrain = board_terrain(&board);
+    let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+    let movement = Movement::default();
+    let mut hpa = Hpa::new(42, 42, 10, movement, lookup);
+
+    let mut checked = 0;
+    for idx in 1..=5 {
+        let start = read_positions(&format!("inputs/agent_{}", idx))[0];
+        let mut goals = read_positions(&format!("inputs/factories_{}", idx));
+        goals.truncate(20);
+        for goal in goals {
+            let mut stats = SearchStats::default();
+            let optimal = a_star(start, goal, 42, 42, lookup, &movement, 1, 0, &mut stats)
+                .map(|moves| path_cost(start, goal, &moves, &terrain, &movement));
+            let cost = hpa
+                .find_path(start, goal, &mut stats)
+                .map(|moves| path_cost(start, goal, &moves, &terrain, &movement));
+            assert_eq!(
+                cost.is_some(),
+                optimal.is_some(),
+                "{:?} -> {:?}",
+                start,
+                goal
+            );
+            if let (Some(cost), Some(optimal)) = (cost, optimal) {
+                assert!(cost >= optimal && cost * 4 <= optimal * 5);
+                checked += 1;
+            }
+        }
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn hierarchical_update_only_rebuilds_affected_clusters() {
+    let board = Board::new(42, 42);
+    let mut terrain = board_terrain(&board);
+    let mut hpa = Hpa::new(42, 42, 10, Movement::default(), |x, y| {
+        terrain[x as usize][y as usize]
+    });
+    assert_eq!(hpa.update(|x, y| terrain[x as usize][y as usize]), 0);
+
+    // Célula no meio de um cluster: as bordas não mudam e só ele é refeito.
+    terrain[15][15] = Terrain::Obstacle;
+    let rebuilt = hpa.update(|x, y| terrain[x as usize][y as usize]);
+    assert_eq!(rebuilt, 1);
+
+    let mut stats = SearchStats::default();
+    let moves = hpa.find_path((14, 15), (16, 15), &mut stats).unwrap();
+    assert_eq!(follow((14, 15), &moves, &terrain), (16, 15));
+
+    // Célula na borda entre (1, 1) e (2, 1): os dois lados são refeitos.
+    terrain[19][15] = Terrain::Obstacle;
+    let rebuilt = hpa.update(|x, y| terrain[x as usize][y as usize]);
+    assert_eq!(rebuilt, 2);
+
+    let moves = hpa.find_path((18, 15), (20, 15), &mut stats).unwrap();
+    assert_eq!(follow((18, 15), &moves, &terrain), (20, 15));
+}
+
+#[test]
+fn stored_connections_are_dropped_when_the_cluster_changes() {
+    let mut terrain = vec![vec![Terrain::Grass; 42]; 42];
+    let mut hpa = Hpa::new(42, 42, 10, Movement::default(), |x, y| {
+        terrain[x as usize][y as usize]
+    });
+    let mut stats = SearchStats::default();
+    let moves = hpa.find_path((14, 15), (25, 15), &mut stats).unwrap();
+    assert_eq!(follow((14, 15), &moves, &terrain), (25, 15));
+
+    // Cercada, a origem não chega mais às entradas do cluster.
+    for (x, y) in [(13, 15), (15, 15), (14, 14), (14, 16)] {
+        terrain[x][y] = Terrain::Obstacle;
+    }
+    hpa.update(|x, y| terrain[x as usize][y as usize]);
+    assert_eq!(hpa.find_path((14, 15), (25, 15), &mut stats), None);
+}
@@ -0,0 +1,52 @@
+use robozinho::board::*;
+use robozinho::jps::jump_point_search;
+use robozinho::movement::Movement;
+use robozinho::search::{a_star, SearchStats};
+
+mod common;
+use common::*;
+
+#[test]
+fn jump_point_search_expands_less_than_a_star_on_shipped_scenarios() {
+    let board = Board::new(42, 42);
+    let terrain = board_terrain(&board);
+    let movement = Movement::default();
+
+    for idx in 1..=5 {
+        let start = read_positions(&format!("inputs/agent_{}", idx))[0];
+        let mut goals = read_positions(&format!("inputs/factories_{}", idx));
+        goals.truncate(20);
+
+        let mut a_star_stats = SearchStats::default();
+        let mut jps_stats = SearchStats::default();
+        for goal in goals {
+            let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+            let a_star_path = a_star(
+                start,
+                goal,
+                42,
+                42,
+                lookup,
+                &movement,
+                1,
+                1,
+                &mut a_star_stats,
+            );
+            let jps_path =
+                jump_point_search(start, goal, 42, 42, lookup, &movement, 1, 1, &mut jps_stats);
+            if let (Some(a_star_path), Some(jps_path)) = (a_star_path, jps_path) {
+                assert!(
+                    path_cost(start, goal, &jps_path, &terrain, &movement)
+                        <= path_cost(start, goal, &a_star_path, &terrain, &movement)
+                );
+            }
+        }
+        assert!(
+            jps_stats.expansions < a_star_stats.expansions,
+            "scenario {}: {} JPS expansions vs {} A* expansions",
+            idx,
+            jps_stats.expansions,
+            a_star_stats.expansions
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use robozinho::board::*;
+use robozinho::dstar::DStarLite;
+use robozinho::movement::Movement;
+use robozinho::poi::PoiCache;
+use robozinho::search::SearchStats;
+use robozinho::terrain::Terrain;
+
+mod common;
+use common::*;
+
+fn optimal(start: (i32, i32), goal: (i32, i32), terrain: &[Vec<Terrain>]) -> i64 {
+    let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+    let mut stats = SearchStats::default();
+    let mut dstar = DStarLite::new(start, goal, 42, 42, Movement::default());
+    let moves = dstar.replan(start, lookup, &mut stats).unwrap();
+    path_cost(start, goal, &moves, terrain, &Movement::default()) as i64
+}
+
+#[test]
+fn cached_distances_match_the_optimal_search() {
+    let board = Board::new(42, 42);
+    let terrain = board_terrain(&board);
+    let start = read_positions("inputs/agent_1")[0];
+    let mut factories = read_positions("inputs/factories_1");
+    factories.truncate(6);
+
+    let mut cache = PoiCache::new(42, 42, Movement::default());
+    cache.refresh(|x, y| terrain[x as usize][y as usize]);
+    cache.warm(&factories);
+    assert_eq!(cache.computed, factories.len());
+
+    for a in &factories {
+        assert_eq!(
+            cache.distance(start, *a),
+            Some(optimal(start, *a, &terrain))
+        );
+        for b in &factories {
+            if a != b {
+                assert_eq!(cache.distance(*a, *b), Some(optimal(*a, *b, &terrain)));
+            }
+        }
+    }
+    // Tudo já estava em cache, nenhum campo novo foi calculado.
+    assert_eq!(cache.computed, factories.len());
+
+    let order = cache.tour(start, &factories);
+    let mut sorted = order.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..factories.len()).collect::<Vec<_>>());
+}
+
+#[test]
+fn terrain_changes_invalidate_the_cache() {
+    let mut terrain = vec![vec![Terrain::Grass; 42]; 42];
+    let mut cache = PoiCache::new(42, 42, Movement::default());
+    cache.refresh(|x, y| terrain[x as usize][y as usize]);
+    assert!(!cache.refresh(|x, y| terrain[x as usize][y as usize]));
+
+    let poi = (20, 20);
+    let before = cache.distance((20, 22), poi).unwrap();
+    terrain[20][21] = Terrain::Obstacle;
+    assert!(cache.refresh(|x, y| terrain[x as usize][y as usize]));
+    let after = cache.distance((20, 22), poi).unwrap();
+    assert_eq!(cache.computed, 2);
+    assert_eq!((before, after), (2, 4));
+}
@@ -0,0 +1,213 @@
+use proptest::prelude::*;
+use robozinho::bidirectional::bidirectional_search;
+use robozinho::factory::Factory;
+use robozinho::movement::{Connectivity, Movement};
+use robozinho::params::Params;
+use robozinho::search::{a_star, SearchStats};
+use robozinho::sim::Simulation;
+use robozinho::terrain::Terrain;
+use robozinho::tool::ToolType;
+use robozinho::trajectory::TrajectoryEvent;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+fn terrain_strategy() -> impl Strategy<Value = Terrain> {
+    prop_oneof![
+        4 => Just(Terrain::Grass),
+        1 => Just(Terrain::Mountain),
+        1 => Just(Terrain::Swamp),
+        1 => Just(Terrain::Desert),
+        2 => Just(Terrain::Obstacle),
+    ]
+}
+
+type Grid = (Vec<Vec<Terrain>>, (i32, i32), (i32, i32));
+
+// Mapa `[x][y]` de 2 a 12 células de cada lado, com uma origem e um destino
+// livres.
+fn grid_strategy() -> impl Strategy<Value = Grid> {
+    (2..12usize, 2..12usize)
+        .prop_flat_map(|(width, height)| {
+            (
+                prop::collection::vec(prop::collection::vec(terrain_strategy(), height), width),
+                (0..width as i32, 0..height as i32),
+                (0..width as i32, 0..height as i32),
+            )
+        })
+        .prop_map(|(mut terrain, start, goal)| {
+            terrain[start.0 as usize][start.1 as usize] = Terrain::Grass;
+            terrain[goal.0 as usize][goal.1 as usize] = Terrain::Grass;
+            (terrain, start, goal)
+        })
+}
+
+// Dijkstra de referência, sem nada em comum com os planejadores.
+fn optimal_cost(terrain: &[Vec<Terrain>], start: (i32, i32), goal: (i32, i32)) -> Option<usize> {
+    let (width, height) = (terrain.len() as i32, terrain[0].len() as i32);
+    let mut best = vec![vec![usize::MAX; height as usize]; width as usize];
+    let mut heap = BinaryHeap::new();
+    best[start.0 as usize][start.1 as usize] = 0;
+    heap.push(Reverse((0, start)));
+    while let Some(Reverse((cost, (x, y)))) = heap.pop() {
+        if (x, y) == goal {
+            return Some(cost);
+        }
+        if cost > best[x as usize][y as usize] {
+            continue;
+        }
+        for (mx, my) in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
+            let (nx, ny) = (x + mx, y + my);
+            if nx < 0 || ny < 0 || nx >= width || ny >= height {
+                continue;
+            }
+            let next = terrain[nx as usize][ny as usize];
+            if !next.passable() {
+                continue;
+            }
+            let n_cost = cost + next.cost();
+            if n_cost < best[nx as usize][ny as usize] {
+                best[nx as usize][ny as usize] = n_cost;
+                heap.push(Reverse((n_cost, (nx, ny))));
+            }
+        }
+    }
+    None
+}
+
+// Segue os movimentos conferindo limites e obstáculos e devolve a soma dos custos
+// de terreno das células por onde o caminho entra.
+fn walk(
+    terrain: &[Vec<Terrain>],
+    start: (i32, i32),
+    goal: (i32, i32),
+    moves: &[(i32, i32)],
+    movement: &Movement,
+) -> usize {
+    let (width, height) = (terrain.len() as i32, terrain[0].len() as i32);
+    let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+    let (mut x, mut y) = start;
+    let mut cost = 0;
+    for &(mx, my) in moves {
+        assert!(movement.can_move((x, y), (mx, my), width, height, lookup));
+        x += mx;
+        y += my;
+        assert!(terrain[x as usize][y as usize].passable());
+        cost += movement.step_cost((mx, my), terrain[x as usize][y as usize]);
+    }
+    assert_eq!((x, y), goal);
+    cost
+}
+
+// A desce para o deserto com custo 21, mas M, que sai da fila antes, chega ao
+// mesmo deserto com 25. O caminho guardado tem que continuar sendo o de A.
+//   S A
+//   M D
+#[test]
+fn a_worse_route_does_not_replace_a_queued_one() {
+    let mut terrain = vec![vec![Terrain::Grass; 2]; 2];
+    terrain[0][1] = Terrain::Mountain;
+    terrain[1][1] = Terrain::Desert;
+    let movement = Movement::default();
+    let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+    let mut stats = SearchStats::default();
+    let path = a_star((0, 0), (1, 1), 2, 2, lookup, &movement, 1, 0, &mut stats).unwrap();
+    assert_eq!(walk(&terrain, (0, 0), (1, 1), &path, &movement), 21);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn dijkstra_is_optimal((terrain, start, goal) in grid_strategy()) {
+        prop_assume!(start != goal);
+        let movement = Movement::default();
+        let (width, height) = (terrain.len() as i32, terrain[0].len() as i32);
+        let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+        let optimal = optimal_cost(&terrain, start, goal);
+
+        let mut stats = SearchStats::default();
+        let path = a_star(start, goal, width, height, lookup, &movement, 1, 0, &mut stats);
+        let cost = path.map(|moves| walk(&terrain, start, goal, &moves, &movement));
+        prop_assert_eq!(cost, optimal);
+
+        let mut stats = SearchStats::default();
+        let path = bidirectional_search(start, goal, width, height, lookup, &movement, false, &mut stats);
+        let cost = path.map(|moves| walk(&terrain, start, goal, &moves, &movement));
+        prop_assert_eq!(cost, optimal);
+    }
+
+    #[test]
+    fn paths_stay_on_passable_cells(
+        (terrain, start, goal) in grid_strategy(),
+        (g_factor, h_factor) in prop_oneof![Just((1, 1)), Just((1, 0)), Just((0, 1))],
+        diagonal in any::<bool>(),
+    ) {
+        prop_assume!(start != goal);
+        let movement = if diagonal {
+            Movement::new(Connectivity::Eight, std::f32::consts::SQRT_2, false)
+        } else {
+            Movement::default()
+        };
+        let (width, height) = (terrain.len() as i32, terrain[0].len() as i32);
+        let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+        let mut stats = SearchStats::default();
+        let path = a_star(start, goal, width, height, lookup, &movement, g_factor, h_factor, &mut stats);
+        // Sem diagonais, qualquer caminho existe exatamente quando o de referência existe.
+        if !diagonal {
+            prop_assert_eq!(path.is_some(), optimal_cost(&terrain, start, goal).is_some());
+        }
+        if let Some(moves) = path {
+            let cost = walk(&terrain, start, goal, &moves, &movement);
+            if let Some(optimal) = optimal_cost(&terrain, start, goal).filter(|_| !diagonal) {
+                prop_assert!(cost >= optimal);
+            }
+        }
+    }
+
+    // O custo de cada plano registrado é a soma dos custos de terreno dos passos
+    // que o agente dá até o destino, e nenhum passo cai em obstáculo.
+    #[test]
+    fn planned_cost_matches_the_steps_taken(
+        (terrain, start, goal) in grid_strategy(),
+        seed in any::<u64>(),
+    ) {
+        prop_assume!(start != goal);
+        let tool = (goal.0 as usize, goal.1 as usize, ToolType::Battery);
+        let factory = Factory::new(start.0 as usize, start.1 as usize, Some(ToolType::Battery), 1);
+        let params = Params::new(vec![(ToolType::Battery, 1)], vec![(ToolType::Battery, 1)], 100, 1, 1, 0);
+        let mut sim = Simulation::new(
+            terrain.clone(),
+            &[tool],
+            &[factory],
+            (start.0 as usize, start.1 as usize),
+            params,
+            seed,
+        )
+        .unwrap();
+        sim.run(2_000);
+
+        let mut planned: Option<(usize, (usize, usize))> = None;
+        let mut walked = 0;
+        for event in &sim.log.events {
+            match *event {
+                TrajectoryEvent::Plan { to, cost, .. } => {
+                    planned = cost.map(|cost| (cost, to));
+                    walked = 0;
+                }
+                TrajectoryEvent::Move { x, y, cost, random } => {
+                    prop_assert!(terrain[x][y].passable());
+                    prop_assert_eq!(cost, terrain[x][y].cost());
+                    if !random {
+                        walked += cost;
+                        if let Some((cost, to)) = planned {
+                            if to == (x, y) {
+                                prop_assert_eq!(walked, cost);
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use robozinho::batch::strategy;
+use robozinho::params::Params;
+use robozinho::sim::{Simulation, Unsupported};
+use std::env;
+use std::fs;
+
+// As estratégias dos exemplos robozinho_estrela, robozinho_guloso e
+// robozinho_uniforme.
+const STRATEGIES: [&str; 3] = ["estrela", "guloso", "uniforme"];
+const SEED: u64 = 42;
+
+// Uma linha por estratégia: nome, custo final, expansões, passos aleatórios e o
+// custo deles. Para regravar depois de uma mudança intencional:
+// UPDATE_GOLDEN=1 cargo test --test regression
+#[test]
+fn shipped_scenarios_match_golden_files() {
+    for idx in 1..=5 {
+        let mut actual = String::new();
+        for name in STRATEGIES {
+            let params = strategy(name).unwrap().params(idx);
+            let mut sim = Simulation::scenario(42, 42, params, SEED).unwrap();
+            let summary = sim.run(1_000_000);
+            assert!(summary.finished, "{} did not finish scenario {}", name, idx);
+            assert!(sim.log.pending.is_empty());
+            actual += &format!(
+                "{} {} {} {} {}\n",
+                name,
+                summary.cost,
+                summary.expansions,
+                summary.random_moves,
+                summary.random_moves_cost
+            );
+        }
+        let path = format!("tests/golden/scenario_{}.txt", idx);
+        if env::var("UPDATE_GOLDEN").is_ok() {
+            fs::write(&path, &actual).expect("Something went wrong");
+            continue;
+        }
+        let expected = fs::read_to_string(&path).expect("Something went wrong");
+        assert_eq!(actual, expected, "scenario {} changed", idx);
+    }
+}
+
+#[test]
+fn unsupported_params_are_an_error() {
+    let params = Params {
+        fog_of_war: true,
+        ..Params::for_scenario(1)
+    };
+    let error = Simulation::scenario(42, 42, params, SEED).err();
+    assert_eq!(error, Some(Unsupported::FogOfWar));
+}
@@ -0,0 +1,77 @@
+use robozinho::batch::{plan, run_batch, to_csv, STRATEGIES};
+use robozinho::report::*;
+
+fn record(strategy: &str, repetition: usize, cost: usize, finished: bool) -> Record {
+    Record {
+        scenario: 1,
+        strategy: strategy.to_string(),
+        repetition,
+        seed: repetition as u64,
+        finished,
+        cost,
+        expansions: cost * 10,
+        random_moves_cost: 0,
+        runtime_us: 100,
+    }
+}
+
+#[test]
+fn distribution_of_known_values() {
+    let d = Distribution::new(&[4.0, 1.0, 3.0, 2.0, 5.0]).unwrap();
+    assert_eq!(d.count, 5);
+    assert_eq!(
+        (d.min, d.q1, d.median, d.q3, d.max),
+        (1.0, 2.0, 3.0, 4.0, 5.0)
+    );
+    assert_eq!(d.mean, 3.0);
+    assert!((d.std_dev - 2.5f64.sqrt()).abs() < 1e-9);
+    assert_eq!(Distribution::new(&[]), None);
+}
+
+#[test]
+fn paired_comparison_skips_unfinished_runs() {
+    let records = vec![
+        record("a", 0, 10, true),
+        record("b", 0, 12, true),
+        record("a", 1, 20, true),
+        record("b", 1, 20, true),
+        record("a", 2, 30, true),
+        record("b", 2, 26, true),
+        record("a", 3, 5, true),
+        record("b", 3, 99, false),
+    ];
+    let c = compare(&records, "a", "b", Metric::Cost);
+    assert_eq!((c.pairs, c.wins, c.losses, c.ties), (3, 1, 1, 1));
+    // Diferenças -2, 0 e 4: média 2/3, desvio sqrt(28/3), t de 2 graus 4,303.
+    assert!((c.mean_difference - 2.0 / 3.0).abs() < 1e-9);
+    let margin = 4.303 * (28.0f64 / 3.0).sqrt() / 3.0f64.sqrt();
+    let (low, high) = c.interval.unwrap();
+    assert!((low - (2.0 / 3.0 - margin)).abs() < 1e-9);
+    assert!((high - (2.0 / 3.0 + margin)).abs() < 1e-9);
+
+    let report = Report::new(&records);
+    assert_eq!(report.strategies, vec!["a", "b"]);
+    assert_eq!(report.unfinished, vec![0, 1]);
+}
+
+#[test]
+fn report_from_a_batch() {
+    let specs = plan(&[1, 2], &STRATEGIES[..3], 2, 42);
+    let results = run_batch(&specs, 42, 42, 0, |_, _| {});
+    let records = parse_csv(&to_csv(&results));
+    assert_eq!(records.len(), specs.len());
+    assert_eq!(records[0], Record::from_result(&results[0]));
+
+    let report = Report::new(&records);
+    assert_eq!(report.scenarios, vec![1, 2]);
+    // Três pares de estratégias para cada uma das quatro métricas.
+    assert_eq!(report.comparisons.len(), 12);
+    assert!(report.comparisons.iter().all(|c| c.pairs == 4));
+
+    let text = markdown(&report);
+    assert!(text.contains("![cost](cost_box.svg)"));
+    assert!(text.contains("| estrela | guloso | 4 |"));
+    let page = html(&report);
+    assert_eq!(page.matches("<svg").count(), 8);
+    assert_eq!(page.matches("<svg").count(), page.matches("</svg>").count());
+}
@@ -0,0 +1,113 @@
+use rand::Rng;
+use robozinho::movement::{Connectivity, Movement};
+use robozinho::rng::SimRng;
+use robozinho::terrain::Terrain;
+use robozinho::tool::ToolType;
+use robozinho::trajectory::*;
+
+fn sample_log() -> TrajectoryLog {
+    let mut log = TrajectoryLog {
+        input_idx: 3,
+        seed: 1234,
+        movement: Movement::new(Connectivity::Eight, 1.5, true),
+        ..TrajectoryLog::default()
+    };
+    log.push(TrajectoryEvent::Plan {
+        from: (0, 0),
+        to: (0, 2),
+        cost: Some(6),
+    });
+    log.push(TrajectoryEvent::Move {
+        x: 0,
+        y: 1,
+        cost: 1,
+        random: false,
+    });
+    log.push(TrajectoryEvent::Move {
+        x: 0,
+        y: 2,
+        cost: 5,
+        random: false,
+    });
+    log.push(TrajectoryEvent::Pickup {
+        x: 0,
+        y: 2,
+        tool: ToolType::SuctionPump,
+    });
+    log.push(TrajectoryEvent::Plan {
+        from: (0, 2),
+        to: (9, 9),
+        cost: None,
+    });
+    log.push(TrajectoryEvent::Terrain {
+        x: 4,
+        y: 4,
+        terrain: Terrain::Obstacle,
+    });
+    log.push(TrajectoryEvent::Move {
+        x: 1,
+        y: 2,
+        cost: 10,
+        random: true,
+    });
+    log.push(TrajectoryEvent::Drop {
+        x: 1,
+        y: 2,
+        tool: ToolType::SuctionPump,
+        quantity: 1,
+    });
+    log.push(TrajectoryEvent::Deliver { x: 1, y: 2 });
+    log.push(TrajectoryEvent::Tool {
+        x: 7,
+        y: 8,
+        tool: ToolType::CoolingDevice,
+    });
+    log
+}
+
+#[test]
+fn text_round_trip_keeps_every_event() {
+    let log = sample_log();
+    let loaded = TrajectoryLog::from_text(&log.to_text()).unwrap();
+    assert_eq!(loaded.input_idx, 3);
+    assert_eq!(loaded.seed, 1234);
+    assert_eq!(loaded.movement, log.movement);
+    assert_eq!(loaded.events, log.events);
+}
+
+#[test]
+fn logs_without_movement_use_the_default() {
+    let loaded = TrajectoryLog::from_text("input 1\nseed 2\nmove 0 1 1\n").unwrap();
+    assert_eq!(loaded.movement, Movement::default());
+}
+
+#[test]
+fn invalid_lines_are_rejected() {
+    let error = |text: &str| TrajectoryLog::from_text(text).err().unwrap();
+    assert!(error("input 1\nseed 2\npickup 0 1 hammer\n").starts_with("Unknown tool"));
+    assert!(error("input 1\nseed 2\nterrain 0 1 lava\n").starts_with("Unknown terrain"));
+    assert!(error("input 1\nseed 2\nteleport 0 1\n").starts_with("Unknown trajectory line"));
+    assert!(error("input 1\nseed 2\ndrop 0 1 battery\n").starts_with("Expected 5 fields"));
+    assert!(error("input 1\nseed 2\nmove 0 x 1\n").starts_with("Invalid value"));
+    assert!(error("input 1\nmovement six 1.5 false\n").starts_with("Invalid value"));
+}
+
+#[test]
+fn replay_advances_one_move_per_tick() {
+    let mut log = sample_log();
+    let mut ticks: Vec<usize> = vec![];
+    while !log.finished() {
+        ticks.push(log.next_tick().len());
+    }
+    // plan + move, move + pickup + plan + terrain, move + drop + deliver + tool
+    assert_eq!(ticks, vec![2, 4, 4]);
+}
+
+#[test]
+fn same_seed_draws_the_same_sequence() {
+    let mut a = SimRng::new(42);
+    let mut b = SimRng::new(42);
+    for _ in 0..100 {
+        assert_eq!(a.gen_range(0..1000), b.gen_range(0..1000));
+    }
+}
@@ -0,0 +1,116 @@
+use robozinho::bidirectional::bidirectional_search;
+use robozinho::board::*;
+use robozinho::dstar::DStarLite;
+use robozinho::ida::ida_star;
+use robozinho::movement::{Connectivity, Movement};
+use robozinho::search::SearchStats;
+use robozinho::weighted::weighted_a_star;
+
+mod common;
+use common::*;
+
+fn check_variants(movement: Movement) {
+    let board = Board::new(42, 42);
+    let terrain = board_terrain(&board);
+    let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+    let mut ida_checked = 0;
+
+    for idx in 1..=5 {
+        let start = read_positions(&format!("inputs/agent_{}", idx))[0];
+        let mut goals = read_positions(&format!("inputs/factories_{}", idx));
+        goals.truncate(5);
+        for goal in goals {
+            if goal == start {
+                continue;
+            }
+            let mut stats = SearchStats::default();
+            let mut dstar = DStarLite::new(start, goal, 42, 42, movement);
+            let optimal = dstar
+                .replan(start, lookup, &mut stats)
+                .map(|moves| path_cost(start, goal, &moves, &terrain, &movement))
+                .unwrap();
+
+            for heuristic in [false, true] {
+                let mut stats = SearchStats::default();
+                let moves = bidirectional_search(
+                    start, goal, 42, 42, lookup, &movement, heuristic, &mut stats,
+                )
+                .unwrap();
+                assert_eq!(path_cost(start, goal, &moves, &terrain, &movement), optimal);
+                // A sobreposição e o passo a passo do relógio leem estas listas.
+                assert_eq!(stats.closed.len(), stats.expansions);
+                assert!(!stats.opened.is_empty());
+            }
+
+            let moves =
+                weighted_a_star(start, goal, 42, 42, lookup, &movement, 1.5, &mut stats).unwrap();
+            let cost = path_cost(start, goal, &moves, &terrain, &movement);
+            assert!(cost as f64 <= optimal as f64 * 1.5);
+            assert!(stats.peak_open > 0);
+        }
+
+        // O IDA* não guarda os nós visitados e, com custos de terreno muito
+        // diferentes, só alcança dentro do limite destinos próximos e baratos; o
+        // limite estourado tem teste próprio.
+        for (mx, my) in [(2, 0), (-2, 0), (0, 2), (0, -2), (1, 1), (-1, -1)] {
+            let goal = (start.0 + mx, start.1 + my);
+            if goal.0 < 0 || goal.1 < 0 || goal.0 >= 42 || goal.1 >= 42 {
+                continue;
+            }
+            if !lookup(goal.0, goal.1).passable() {
+                continue;
+            }
+            let mut stats = SearchStats::default();
+            let mut dstar = DStarLite::new(start, goal, 42, 42, movement);
+            let optimal = dstar
+                .replan(start, lookup, &mut stats)
+                .map(|moves| path_cost(start, goal, &moves, &terrain, &movement))
+                .unwrap();
+            if optimal > 10 * movement.scale() {
+                continue;
+            }
+            ida_checked += 1;
+            let moves =
+                ida_star(start, goal, 42, 42, lookup, &movement, 200_000, &mut stats).unwrap();
+            assert_eq!(path_cost(start, goal, &moves, &terrain, &movement), optimal);
+        }
+    }
+    assert!(ida_checked > 0);
+}
+
+#[test]
+fn variants_respect_their_bounds_with_four_neighbours() {
+    check_variants(Movement::default());
+}
+
+#[test]
+fn variants_respect_their_bounds_with_eight_neighbours() {
+    check_variants(Movement::new(
+        Connectivity::Eight,
+        std::f32::consts::SQRT_2,
+        false,
+    ));
+}
+
+#[test]
+fn ida_gives_up_when_the_expansion_limit_is_exceeded() {
+    let board = Board::new(42, 42);
+    let terrain = board_terrain(&board);
+    let lookup = |x: i32, y: i32| terrain[x as usize][y as usize];
+    let start = read_positions("inputs/agent_1")[0];
+    let goal = read_positions("inputs/factories_1")[0];
+
+    let mut stats = SearchStats::default();
+    let moves = ida_star(
+        start,
+        goal,
+        42,
+        42,
+        lookup,
+        &Movement::default(),
+        1_000,
+        &mut stats,
+    );
+    assert_eq!(moves, None);
+    assert!(stats.expansions <= 1_000);
+}
@@ -0,0 +1,16 @@
+use robozinho::board::Board;
+use robozinho::grid::{GridCoord, GridLayout};
+
+#[test]
+fn layout_fills_the_window_after_resizing() {
+    let board = Board::new(42, 42);
+    for (width, height) in [(700., 700.), (1280., 720.), (400., 900.)] {
+        let layout = GridLayout::new(width, height, &board);
+        let first = layout.center(GridCoord::new(0, 0));
+        let last = layout.center(GridCoord::new(41, 41));
+        assert!((first.x - layout.cell_width / 2. + width / 2.).abs() < 1e-3);
+        assert!((first.y - layout.cell_height / 2. + height / 2.).abs() < 1e-3);
+        assert!((last.x + layout.cell_width / 2. - width / 2.).abs() < 1e-3);
+        assert!((last.y + layout.cell_height / 2. - height / 2.).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,22 @@
+use robozinho::terrain::Terrain;
+use robozinho::world_event::WorldEvents;
+
+#[test]
+fn terrain_change_on_a_blocked_cell_waits_for_the_block_to_end() {
+    let mut events = WorldEvents::default();
+    events.block(10, 3, 4, Terrain::Grass);
+    assert_eq!(events.change_terrain(3, 4, Terrain::Swamp), None);
+    assert_eq!(
+        events.change_terrain(5, 5, Terrain::Desert),
+        Some(Terrain::Desert)
+    );
+
+    // Outro bloqueio estende o prazo sem perder a mudança.
+    events.block(12, 3, 4, Terrain::Obstacle);
+    assert!(events.unblock(11).is_empty());
+    assert_eq!(events.unblock(12), vec![(3, 4, Terrain::Swamp)]);
+    assert_eq!(
+        events.change_terrain(3, 4, Terrain::Grass),
+        Some(Terrain::Grass)
+    );
+}